@@ -11,4 +11,6 @@ pub enum BuildError {
     SynError(#[from] syn::Error),
     #[error("Expected {_0} to exist, but found None")]
     HashMapExpectedSomeError(String),
+    #[error("Unknown data part reference relationship: {_0}")]
+    UnknownDataPartReferenceError(String),
 }
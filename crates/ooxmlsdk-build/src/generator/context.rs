@@ -1,13 +1,15 @@
-use heck::ToSnakeCase;
+use heck::{ToSnakeCase, ToUpperCamelCase};
 use std::{
     collections::{HashMap, HashSet},
     fs,
     fs::File,
     path::Path,
 };
+use syn::{Type, parse_str};
 
 use crate::{
     error::BuildErrorReport,
+    generator::simple_type::{is_string_simple_type, simple_type_mapping},
     models::{
         OpenXmlNamespace, OpenXmlPart, OpenXmlSchema, OpenXmlSchemaEnum, OpenXmlSchemaType,
         TypedNamespace, TypedSchema,
@@ -31,6 +33,7 @@ pub struct GenContext<'a> {
     pub type_name_namespace_map: HashMap<&'a str, &'a OpenXmlNamespace>,
     pub namespace_typed_namespace_map: HashMap<&'a str, &'a TypedNamespace>,
     pub part_name_type_name_map: HashMap<&'a str, &'a str>,
+    pub part_name_part_map: HashMap<&'a str, &'a OpenXmlPart>,
 }
 
 impl<'a> GenContext<'a> {
@@ -276,6 +279,81 @@ impl<'a> GenContext<'a> {
     }
 }
 
+impl GenContext<'_> {
+    /// Whether the borrowed representation of a type differs from the owned one, i.e. the type
+    /// holds strings itself or through its children. Other types are shared by both.
+    pub(crate) fn is_borrowed_type(&self, schema_type: &OpenXmlSchemaType) -> bool {
+        if schema_type.is_composite() {
+            return true;
+        }
+
+        let (type_base_class, _) = schema_type.split_name();
+
+        let mut attributes = schema_type.attributes.iter().collect::<Vec<_>>();
+
+        let mut content_type_name =
+            (schema_type.base_class == "OpenXmlLeafTextElement").then_some(type_base_class);
+
+        if schema_type.is_derived
+            && let Some(base_class_type) = self
+                .type_name_type_map
+                .get(format!("{type_base_class}/").as_str())
+        {
+            if base_class_type.is_composite() {
+                return true;
+            }
+
+            attributes.extend(&base_class_type.attributes);
+
+            if schema_type.children.is_empty()
+                && base_class_type.base_class == "OpenXmlLeafTextElement"
+            {
+                content_type_name = Some(base_class_type.split_name().0);
+            }
+        }
+
+        if attributes
+            .iter()
+            .any(|attr| is_string_simple_type(&attr.r#type))
+        {
+            return true;
+        }
+
+        if let Some(content_type_name) = content_type_name
+            && !self.enum_type_enum_map.contains_key(content_type_name)
+            && is_string_simple_type(simple_type_mapping(content_type_name))
+        {
+            return true;
+        }
+
+        return schema_type.children.iter().any(|child| {
+            self.type_name_type_map
+                .get(child.name.as_str())
+                .is_some_and(|child_type| self.is_borrowed_type(child_type))
+        });
+    }
+
+    /// Returns the path of a schema type, in `crate::borrowed` if `borrowed` is set and the type
+    /// has a borrowed representation.
+    pub(crate) fn schema_type_path(&self, schema_type: &OpenXmlSchemaType, borrowed: bool) -> Type {
+        let type_name = schema_type.class_name.to_upper_camel_case();
+
+        if borrowed && self.is_borrowed_type(schema_type) {
+            return parse_str(&format!(
+                "crate::borrowed::{}::{type_name}<'de>",
+                schema_type.module_name
+            ))
+            .unwrap();
+        }
+
+        return parse_str(&format!(
+            "crate::schemas::{}::{type_name}",
+            schema_type.module_name
+        ))
+        .unwrap();
+    }
+}
+
 pub(crate) fn gen_type_name_set(
     type_name_set: &mut HashSet<String>,
     type_name: &str,
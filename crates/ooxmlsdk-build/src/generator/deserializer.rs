@@ -1,12 +1,18 @@
 use heck::ToUpperCamelCase;
+use proc_macro2::TokenStream;
 use quote::quote;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::collections::HashSet;
-use syn::{Arm, Ident, ItemFn, LitByteStr, Stmt, Type, parse_quote, parse_str, parse2};
+use syn::{
+    Arm, Expr, Ident, ItemFn, LitByteStr, Signature, Stmt, Type, parse_quote, parse_str, parse2,
+};
 
 use crate::{
     error::*,
-    generator::{context::GenContext, simple_type::simple_type_mapping},
+    generator::{
+        context::{GenContext, check_office_version},
+        simple_type::simple_type_mapping,
+    },
     models::{
         Occurrence, OpenXmlSchema, OpenXmlSchemaEnum, OpenXmlSchemaType,
         OpenXmlSchemaTypeAttribute, OpenXmlSchemaTypeChild, OpenXmlSchemaTypeParticle,
@@ -33,7 +39,7 @@ pub fn gen_deserializers(
         &schema
             .types
             .par_iter()
-            .map(|schema_type| gen_schema_type(schema, schema_type, gen_context))
+            .map(|schema_type| gen_schema_type(schema, schema_type, gen_context, false))
             .collect::<Result<Vec<_>, _>>()?
             .join("\n"),
     );
@@ -41,10 +47,33 @@ pub fn gen_deserializers(
     Ok(contents)
 }
 
+/// Generates the deserializers of the borrowed representation, along with the links from the
+/// owned types to it.
+pub fn gen_borrowed_deserializers(
+    schema: &OpenXmlSchema,
+    gen_context: &GenContext,
+) -> Result<String, BuildErrorReport> {
+    let deserializers = schema
+        .types
+        .par_iter()
+        .filter(|schema_type| gen_context.is_borrowed_type(schema_type))
+        .map(|schema_type| gen_schema_type(schema, schema_type, gen_context, true))
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n");
+
+    if deserializers.trim().is_empty() {
+        return Ok(deserializers);
+    }
+
+    // The borrowed deserializers use the concrete `SliceReader` instead of an `XmlReader` bound.
+    return Ok(format!("use crate::common::XmlReader;\n{deserializers}"));
+}
+
 fn gen_schema_type(
     schema: &OpenXmlSchema,
     schema_type: &OpenXmlSchemaType,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<String, BuildErrorReport> {
     if schema_type.is_abstract {
         return Ok(String::with_capacity(0));
@@ -56,18 +85,28 @@ fn gen_schema_type(
 
     let schema_class_name_formatted = schema_type.class_name.to_upper_camel_case();
 
-    let struct_type: Type = parse_str(&format!(
-        "crate::schemas::{}::{schema_class_name_formatted}",
-        &schema.module_name
-    ))
-    .unwrap();
+    let struct_type = gen_context.schema_type_path(schema_type, borrowed);
+
+    // Strings borrowed from the input are kept as they are in the borrowed representation.
+    let into_owned_opt: Option<TokenStream> = (!borrowed).then(|| quote! { .into_owned() });
+
+    let xml_text_type: Type = if borrowed {
+        parse_quote!(std::borrow::Cow<'de, str>)
+    } else {
+        parse_quote!(String)
+    };
 
-    let (type_base_class, type_prefixed_name) = schema_type.split_name();
-    let (_, type_name_str) = schema_type.split_last_name();
+    let (type_base_class, _) = schema_type.split_name();
+    let (type_prefix, type_name_str) = schema_type.split_last_name();
 
-    let type_prefixed_name_literal: LitByteStr =
-        parse_str(&format!("b\"{type_prefixed_name}\"")).unwrap();
+    let type_namespace_uri_literal: LitByteStr = parse_str(&format!(
+        "b\"{}\"",
+        gen_context.prefix_namespace_map.try_get(type_prefix)?.uri
+    ))
+    .unwrap();
     let type_name_literal: LitByteStr = parse_str(&format!("b\"{type_name_str}\"")).unwrap();
+    let type_name_pattern =
+        gen_qualified_name_pattern(type_prefix, type_name_str, true, gen_context)?;
 
     let mut field_declaration_list: Vec<Stmt> = vec![];
     let mut attr_match_list: Vec<Arm> = vec![];
@@ -82,6 +121,8 @@ fn gen_schema_type(
 
     let mut attributes: Vec<&OpenXmlSchemaTypeAttribute> = vec![];
 
+    let mut has_raw_content = false;
+
     let child_map = schema_type.child_map();
 
     if schema_type.base_class == "OpenXmlLeafTextElement" {
@@ -90,14 +131,16 @@ fn gen_schema_type(
         }
 
         field_declaration_list.push(parse_quote! {
-          let mut xml_content = None;
+          let mut xml_text: Option<#xml_text_type> = None;
         });
 
+        field_unwrap_list.push(gen_simple_content_stmt(type_base_class, gen_context)?);
+
         field_ident_list.push(parse_quote! {
           xml_content
         });
 
-        loop_match_arm_list.push(gen_simple_child_match_arm(type_base_class, gen_context)?);
+        loop_match_arm_list.extend(gen_text_match_arm_list(borrowed));
     } else if schema_type.base_class == "OpenXmlLeafElement" {
         for attr in &schema_type.attributes {
             attributes.push(attr);
@@ -116,9 +159,15 @@ fn gen_schema_type(
               let mut xmlns = None;
             });
 
-            field_declaration_list.push(parse_quote! {
-              let mut xmlns_map = std::collections::HashMap::<String, String>::new();
-            });
+            if borrowed {
+                field_declaration_list.push(parse_quote! {
+                  let mut xmlns_map = vec![];
+                });
+            } else {
+                field_declaration_list.push(parse_quote! {
+                  let mut xmlns_map = std::collections::BTreeMap::<String, String>::new();
+                });
+            }
 
             field_declaration_list.push(parse_quote! {
               let mut mc_ignorable = None;
@@ -133,11 +182,7 @@ fn gen_schema_type(
             attributes.push(attr);
         }
 
-        let child_choice_enum_type: Type = parse_str(&format!(
-            "crate::schemas::{}::{schema_class_name_formatted}ChildChoice",
-            &schema.module_name,
-        ))
-        .unwrap();
+        let child_choice_enum_type = gen_child_choice_enum_type(schema_type, gen_context, borrowed);
 
         if schema_type.is_one_sequence_flatten() {
             for schema_type_particle in &schema_type.particle.items {
@@ -153,8 +198,8 @@ fn gen_schema_type(
                         });
 
                         field_unwrap_list.push(parse_quote! {
-                            let #child_property_name_ident = #child_property_name_ident
-                                .ok_or_else(|| crate::common::SdkError::CommonError(#child_property_name_str.to_string()))?;
+                            let #child_property_name_ident = xml_reader
+                                .recover_missing(#child_property_name_ident, None, #child_property_name_str)?;
                         });
                     }
                     Occurrence::Optional => {
@@ -176,6 +221,7 @@ fn gen_schema_type(
                     child,
                     gen_context,
                     &mut loop_children_suffix_match_set,
+                    borrowed,
                 )?);
             }
         } else {
@@ -195,9 +241,12 @@ fn gen_schema_type(
                     &child_choice_enum_type,
                     gen_context,
                     &mut loop_children_suffix_match_set,
+                    borrowed,
                 )?);
             }
         }
+
+        has_raw_content = true;
     } else if schema_type.is_derived {
         let base_class_type = gen_context
             .type_name_type_map
@@ -226,8 +275,8 @@ fn gen_schema_type(
                         });
 
                         field_unwrap_list.push(parse_quote! {
-                            let #child_property_name_ident = #child_property_name_ident
-                                .ok_or_else(|| crate::common::SdkError::CommonError(#child_property_name_str.to_string()))?;
+                            let #child_property_name_ident = xml_reader
+                                .recover_missing(#child_property_name_ident, None, #child_property_name_str)?;
                         });
                     }
                     Occurrence::Optional => {
@@ -254,19 +303,22 @@ fn gen_schema_type(
             });
         } else if base_class_type.base_class == "OpenXmlLeafTextElement" {
             field_declaration_list.push(parse_quote! {
-              let mut xml_content = None;
+              let mut xml_text: Option<#xml_text_type> = None;
             });
 
+            field_unwrap_list.push(gen_simple_content_stmt(
+                base_class_type.split_name().0,
+                gen_context,
+            )?);
+
             field_ident_list.push(parse_quote! {
               xml_content
             });
+
+            loop_match_arm_list.extend(gen_text_match_arm_list(borrowed));
         }
 
-        let child_choice_enum_type: Type = parse_str(&format!(
-            "crate::schemas::{}::{schema_class_name_formatted}ChildChoice",
-            &schema.module_name,
-        ))
-        .map_err(BuildError::from)?;
+        let child_choice_enum_type = gen_child_choice_enum_type(schema_type, gen_context, borrowed);
 
         if schema_type.is_one_sequence_flatten() && base_class_type.composite_type == "OneSequence"
         {
@@ -278,6 +330,7 @@ fn gen_schema_type(
                     child,
                     gen_context,
                     &mut loop_children_suffix_match_set,
+                    borrowed,
                 )?);
             }
         } else {
@@ -287,15 +340,12 @@ fn gen_schema_type(
                     &child_choice_enum_type,
                     gen_context,
                     &mut loop_children_suffix_match_set,
+                    borrowed,
                 )?);
             }
         }
 
-        if schema_type.children.is_empty() && base_class_type.base_class == "OpenXmlLeafTextElement"
-        {
-            let base_first_name = base_class_type.split_name().0;
-            loop_match_arm_list.push(gen_simple_child_match_arm(base_first_name, gen_context)?);
-        }
+        has_raw_content = base_class_type.is_composite();
     } else {
         panic!("{schema_type:?}");
     };
@@ -303,17 +353,18 @@ fn gen_schema_type(
     for attr in &attributes {
         let attr_name_str = attr.as_name_str();
         let attr_name_ident = attr.as_name_ident();
+        let attr_q_name_str = attr.q_name.trim_start_matches(':');
 
         field_declaration_list.push(parse_quote! {
           let mut #attr_name_ident = None;
         });
 
-        attr_match_list.push(gen_field_match_arm(attr, gen_context)?);
+        attr_match_list.push(gen_field_match_arm(attr, gen_context, borrowed)?);
 
         if attr.is_validator_required() {
             field_unwrap_list.push(parse_quote! {
-                let #attr_name_ident = #attr_name_ident
-                  .ok_or_else(|| crate::common::SdkError::CommonError(#attr_name_str.to_string()))?;
+                let #attr_name_ident = xml_reader
+                  .recover_missing(#attr_name_ident, Some(#attr_q_name_str), #attr_name_str)?;
             })
         }
 
@@ -322,7 +373,57 @@ fn gen_schema_type(
 
     let mut expect_event_start_stmt: Stmt = parse_quote! {
         let (e, empty_tag) =
-            crate::common::expect_event_start(xml_reader, xml_event, #type_prefixed_name_literal, #type_name_literal)?;
+            crate::common::expect_event_start(xml_reader, xml_event, #type_namespace_uri_literal, #type_name_literal)?;
+    };
+
+    let raw_attribute_push_stmt: Stmt = if borrowed {
+        parse_quote! {
+            raw_attributes.push(crate::common::BorrowedRawAttribute {
+                name: crate::common::borrowed_str(attr.key.into_inner())?,
+                value: attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?,
+            });
+        }
+    } else {
+        parse_quote! {
+            raw_attributes.push(crate::common::RawAttribute {
+                name: String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                value: attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned(),
+            });
+        }
+    };
+
+    let attributes_expr: Expr = if borrowed {
+        parse_quote!(xml_reader.attributes(&e)?)
+    } else {
+        parse_quote!(e.attributes().with_checks(false))
+    };
+
+    let xmlns_map_insert_stmt: Stmt = if borrowed {
+        parse_quote! {
+            xmlns_map.push((
+                crate::common::borrowed_str(xmlns_key)?,
+                attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?,
+            ));
+        }
+    } else {
+        parse_quote! {
+            xmlns_map.insert(
+                String::from_utf8_lossy(xmlns_key).to_string(),
+                attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned(),
+            );
+        }
+    };
+
+    let attr_default_arm: Arm = if has_raw_content {
+        parse_quote! {
+            _ => {
+                #raw_attribute_push_stmt
+            }
+        }
+    } else {
+        parse_quote! {
+            _ => {}
+        }
     };
 
     let attr_match_stmt_opt: Option<Stmt> = if (schema_type.base_class == "OpenXmlCompositeElement"
@@ -335,50 +436,143 @@ fn gen_schema_type(
             || schema_namespace.uri == "http://schemas.openxmlformats.org/drawingml/2006/picture")
     {
         Some(parse_quote! {
-            for attr in e.attributes().with_checks(false) {
+            for attr in #attributes_expr {
                 let attr = attr.map_err(crate::common::SdkError::from)?;
 
-                match attr.key.as_ref() {
+                if attr.key.as_ref() == b"xmlns" {
+                    xmlns = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)? #into_owned_opt);
+
+                    continue;
+                }
+
+                if let Some(xmlns_key) = attr.key.into_inner().strip_prefix(b"xmlns:") {
+                    #xmlns_map_insert_stmt
+
+                    continue;
+                }
+
+                #[allow(clippy::single_match)]
+                match xml_reader.resolve_attribute(attr.key) {
                     #( #attr_match_list )*
-                    b"xmlns" => {
-                        xmlns = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned());
-                    }
-                    b"mc:Ignorable" => {
-                        mc_ignorable = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned());
-                    }
-                    key => {
-                        if let Some(xmlns_key) = key.strip_prefix(b"xmlns:") {
-                            xmlns_map.insert(
-                                String::from_utf8_lossy(xmlns_key).to_string(),
-                                attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned(),
-                            );
-                        }
+                    (Some(b"http://schemas.openxmlformats.org/markup-compatibility/2006"), b"Ignorable") => {
+                        mc_ignorable = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)? #into_owned_opt);
                     }
+                    #attr_default_arm
                 }
             }
         })
     } else if !attr_match_list.is_empty() {
         Some(parse_quote! {
-          for attr in e.attributes().with_checks(false) {
+          for attr in #attributes_expr {
             let attr = attr.map_err(crate::common::SdkError::from)?;
 
             #[allow(clippy::single_match)]
-            match attr.key.as_ref() {
+            match xml_reader.resolve_attribute(attr.key) {
               #( #attr_match_list )*
-              _ => {}
+              #attr_default_arm
             }
           }
         })
+    } else if has_raw_content {
+        Some(parse_quote! {
+          for attr in #attributes_expr {
+            let attr = attr.map_err(crate::common::SdkError::from)?;
+
+            #raw_attribute_push_stmt
+          }
+        })
     } else {
         expect_event_start_stmt = parse_quote! {
           let (_, empty_tag) =
-            crate::common::expect_event_start(xml_reader, xml_event, #type_prefixed_name_literal, #type_name_literal)?;
+            crate::common::expect_event_start(xml_reader, xml_event, #type_namespace_uri_literal, #type_name_literal)?;
         };
 
         None
     };
 
-    if !loop_children_match_list.is_empty() {
+    let mut child_position_stmt_opt: Option<Stmt> = None;
+
+    let mut loop_children_default_arm: Arm = parse_quote! {
+      _ => {
+        tracing::warn!(
+          "Skipping non-matching tag: ({}) from schema: ({})",
+          String::from_utf8_lossy(e.name().as_ref()),
+          #schema_class_name_formatted
+        );
+
+        if !e_empty {
+          xml_reader.skip_element()?;
+        }
+
+        continue;
+      },
+    };
+
+    if has_raw_content {
+        field_declaration_list.push(parse_quote! {
+          let mut raw_attributes = vec![];
+        });
+
+        field_declaration_list.push(parse_quote! {
+          let mut raw_children = vec![];
+        });
+
+        field_ident_list.push(parse_quote! {
+          raw_attributes
+        });
+
+        field_ident_list.push(parse_quote! {
+          raw_children
+        });
+
+        let raw_child_position: Expr = if loop_children_match_list.is_empty() {
+            parse_quote!(0)
+        } else {
+            field_declaration_list.push(parse_quote! {
+              let mut child_position = 0;
+            });
+
+            child_position_stmt_opt = Some(parse_quote! {
+              child_position += 1;
+            });
+
+            parse_quote!(child_position)
+        };
+
+        let raw_child_push_stmt: Stmt = if borrowed {
+            parse_quote! {
+              raw_children.push(crate::common::BorrowedRawChild {
+                position: #raw_child_position,
+                xml: xml_reader.read_raw_str(&e, e_empty)?,
+              });
+            }
+        } else {
+            parse_quote! {
+              raw_children.push(crate::common::RawChild {
+                position: #raw_child_position,
+                xml: crate::common::read_raw_xml(xml_reader, e, e_empty)?,
+              });
+            }
+        };
+
+        if loop_children_match_list.is_empty() {
+            loop_children_stmt_opt = Some(parse_quote! {
+              if let Some(e) = e_opt {
+                #raw_child_push_stmt
+              }
+            });
+        }
+
+        loop_children_default_arm = parse_quote! {
+          _ => {
+            #raw_child_push_stmt
+
+            continue;
+          }
+        };
+    }
+
+    if !loop_children_match_list.is_empty() || has_raw_content {
         loop_declaration_list.push(parse_quote! {
           let mut e_opt = None;
         });
@@ -400,29 +594,46 @@ fn gen_schema_type(
           }
         });
 
-        //TODO: Strip out the namespace prefix first
-        loop_children_stmt_opt = Some(parse_quote! {
-          if let Some(e) = e_opt {
-            match e.name().as_ref() {
-              #( #loop_children_match_list )*
-              _ => {
-                tracing::warn!(
-                  "Skipping non-matching tag: ({}) from schema: ({})",
-                  String::from_utf8_lossy(e.name().as_ref()),
-                  #schema_class_name_formatted
-                );
-                continue;
-              },
+        loop_children_stmt_opt.get_or_insert_with(|| {
+            parse_quote! {
+              if let Some(e) = e_opt {
+                match xml_reader.resolve_element(e.name()) {
+                  #( #loop_children_match_list )*
+                  #loop_children_default_arm
+                }
+
+                #child_position_stmt_opt
+              }
             }
+        });
+    }
+
+    if loop_children_stmt_opt.is_none() {
+        loop_match_arm_list.push(parse_quote! {
+          quick_xml::events::Event::Start(_) => {
+            xml_reader.skip_element()?;
           }
-        })
+        });
     }
 
+    let deserialize_fn_signature: Signature = if borrowed {
+        parse_quote! {
+          fn deserialize_borrowed(
+            xml_reader: &mut crate::common::SliceReader<'de>,
+            xml_event: Option<(quick_xml::events::BytesStart<'de>, bool)>,
+          ) -> Result<Self, crate::common::SdkErrorReport>
+        }
+    } else {
+        parse_quote! {
+          fn deserialize_inner<'de>(
+            xml_reader: &mut impl crate::common::XmlReader<'de>,
+            xml_event: Option<(quick_xml::events::BytesStart<'de>, bool)>,
+          ) -> Result<Self, crate::common::SdkErrorReport>
+        }
+    };
+
     let deserialize_inner_fn: ItemFn = parse_quote! {
-      fn deserialize_inner<'de>(
-        xml_reader: &mut impl crate::common::XmlReader<'de>,
-        xml_event: Option<(quick_xml::events::BytesStart<'de>, bool)>,
-      ) -> Result<Self, crate::common::SdkErrorReport> {
+      #deserialize_fn_signature {
         #expect_event_start_stmt
 
         #( #field_declaration_list )*
@@ -435,12 +646,9 @@ fn gen_schema_type(
 
             match xml_reader.next()? {
               #( #loop_match_arm_list )*
-              quick_xml::events::Event::End(e) => match e.name().as_ref() {
-                #type_prefixed_name_literal | #type_name_literal => {
-                  break;
-                }
-                _ => (),
-              },
+              quick_xml::events::Event::End(e) if matches!(xml_reader.resolve_element(e.name()), #type_name_pattern) => {
+                break;
+              }
               quick_xml::events::Event::Eof => Err(crate::common::SdkError::UnknownError)?,
               _ => (),
             }
@@ -457,6 +665,21 @@ fn gen_schema_type(
       }
     };
 
+    if borrowed {
+        let owned_struct_type = gen_context.schema_type_path(schema_type, false);
+
+        return Ok(quote! {
+          impl<'de> crate::common::BorrowedDeserializeable<'de> for #struct_type {
+            #deserialize_inner_fn
+          }
+
+          impl crate::common::HasBorrowed for #owned_struct_type {
+            type Borrowed<'de> = #struct_type;
+          }
+        }
+        .to_string());
+    }
+
     return Ok(quote! {
       impl crate::common::Deserializeable for #struct_type {
         #deserialize_inner_fn
@@ -465,6 +688,120 @@ fn gen_schema_type(
     .to_string());
 }
 
+/// Returns the path of the child choice enum of a type, which the borrowed representation shares
+/// with the owned one if no child is borrowed.
+fn gen_child_choice_enum_type(
+    schema_type: &OpenXmlSchemaType,
+    gen_context: &GenContext,
+    borrowed: bool,
+) -> Type {
+    let module = if borrowed
+        && schema_type.children.iter().any(|child| {
+            gen_context
+                .type_name_type_map
+                .get(child.name.as_str())
+                .is_some_and(|child_type| gen_context.is_borrowed_type(child_type))
+        }) {
+        "borrowed"
+    } else {
+        "schemas"
+    };
+
+    return parse_str(&format!(
+        "crate::{module}::{}::{}ChildChoice",
+        schema_type.module_name,
+        schema_type.class_name.to_upper_camel_case()
+    ))
+    .unwrap();
+}
+
+/// Generates the call deserializing a child element into its type, or its borrowed
+/// representation if `borrowed` is set and the type has one.
+fn gen_child_deserialize_expr(
+    child_type: &OpenXmlSchemaType,
+    gen_context: &GenContext,
+    borrowed: bool,
+) -> Expr {
+    let child_variant_type = gen_context.schema_type_path(child_type, borrowed);
+
+    if !borrowed {
+        return parse_quote! {
+            #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?
+        };
+    }
+
+    if gen_context.is_borrowed_type(child_type) {
+        return parse_quote! {
+            <#child_variant_type as crate::common::BorrowedDeserializeable>::deserialize_borrowed(
+                xml_reader,
+                Some((e, e_empty)),
+            )?
+        };
+    }
+
+    return parse_quote! {
+        <#child_variant_type as crate::common::Deserializeable>::deserialize_inner(
+            xml_reader,
+            Some((e, e_empty)),
+        )?
+    };
+}
+
+pub fn gen_namespace_uri_by_prefix_fn(
+    gen_context: &GenContext,
+) -> Result<ItemFn, BuildErrorReport> {
+    let mut prefix_match_list: Vec<Arm> = vec![];
+
+    for namespace in &gen_context.namespaces {
+        if namespace.prefix.is_empty() {
+            continue;
+        }
+
+        let prefix_literal: LitByteStr =
+            parse_str(&format!("b\"{}\"", namespace.prefix)).map_err(BuildError::from)?;
+        let uri_literal: LitByteStr =
+            parse_str(&format!("b\"{}\"", namespace.uri)).map_err(BuildError::from)?;
+
+        prefix_match_list.push(parse_quote! {
+            #prefix_literal => Some(#uri_literal),
+        });
+    }
+
+    return Ok(parse_quote! {
+        /// Returns the namespace URI of a well-known prefix, used for prefixes that are not
+        /// declared in the document.
+        pub fn namespace_uri_by_prefix(prefix: &[u8]) -> Option<&'static [u8]> {
+            match prefix {
+                #( #prefix_match_list )*
+                _ => None,
+            }
+        }
+    });
+}
+
+pub fn gen_is_namespace_understood_fn(
+    gen_context: &GenContext,
+) -> Result<ItemFn, BuildErrorReport> {
+    let mut uri_literal_list: Vec<LitByteStr> = vec![];
+
+    for namespace in &gen_context.namespaces {
+        if !check_office_version(&namespace.version) {
+            continue;
+        }
+
+        uri_literal_list
+            .push(parse_str(&format!("b\"{}\"", namespace.uri)).map_err(BuildError::from)?);
+    }
+
+    return Ok(parse_quote! {
+        /// Returns whether a namespace is understood by the compiled Office version, as used by
+        /// markup compatibility processing.
+        pub fn is_namespace_understood(namespace_uri: &[u8]) -> bool {
+            matches!(namespace_uri, #( #uri_literal_list )|*)
+        }
+    });
+}
+
 fn gen_schema_enum(
     schema: &OpenXmlSchema,
     schema_enum: &OpenXmlSchemaEnum,
@@ -526,41 +863,36 @@ fn gen_one_sequence_match_arm(
     child: &OpenXmlSchemaTypeChild,
     gen_context: &GenContext,
     loop_children_suffix_match_set: &mut HashSet<String>,
+    borrowed: bool,
 ) -> Result<Arm, BuildErrorReport> {
     let child_type = gen_context
         .type_name_type_map
         .try_get(child.name.as_str())?;
 
-    let (_, child_prefixed_name) = child.split_name();
-    let (_, child_name) = child.split_last_name();
+    let (child_prefix, child_name) = child.split_last_name();
     let child_property_name_ident = child.as_property_name_ident();
 
-    let child_prefixed_name_literal: LitByteStr =
-        parse_str(&format!("b\"{child_prefixed_name}\"")).map_err(BuildError::from)?;
-    let child_name_literal: LitByteStr =
-        parse_str(&format!("b\"{child_name}\"")).map_err(BuildError::from)?;
+    let child_name_pattern =
+        gen_qualified_name_pattern(child_prefix, child_name, false, gen_context)?;
+    let child_name_or_unbound_pattern =
+        gen_qualified_name_pattern(child_prefix, child_name, true, gen_context)?;
 
-    let child_variant_type: Type = parse_str(&format!(
-        "crate::schemas::{}::{}",
-        &child_type.module_name,
-        child_type.class_name.to_upper_camel_case()
-    ))
-    .map_err(BuildError::from)?;
+    let child_deserialize_expr = gen_child_deserialize_expr(child_type, gen_context, borrowed);
 
     // TODO: Simplify again
     if loop_children_suffix_match_set.insert(child_name.to_string()) {
         match schema_type_particle.as_occurrence() {
             Occurrence::Required | Occurrence::Optional => Ok(parse_quote! {
-                #child_prefixed_name_literal | #child_name_literal => {
+                #child_name_or_unbound_pattern => {
                     #child_property_name_ident = Some(std::boxed::Box::new(
-                        #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?,
+                        #child_deserialize_expr,
                     ));
                 }
             }),
             Occurrence::Repeated => Ok(parse_quote! {
-                #child_prefixed_name_literal | #child_name_literal => {
+                #child_name_or_unbound_pattern => {
                     #child_property_name_ident.push(
-                        #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?,
+                        #child_deserialize_expr,
                     );
                 }
             }),
@@ -568,16 +900,16 @@ fn gen_one_sequence_match_arm(
     } else {
         match schema_type_particle.as_occurrence() {
             Occurrence::Required | Occurrence::Optional => Ok(parse_quote! {
-                #child_prefixed_name_literal => {
+                #child_name_pattern => {
                     #child_property_name_ident = Some(std::boxed::Box::new(
-                        #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?,
+                        #child_deserialize_expr,
                     ));
                 }
             }),
             Occurrence::Repeated => Ok(parse_quote! {
-                #child_prefixed_name_literal => {
+                #child_name_pattern => {
                     #child_property_name_ident.push(
-                        #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?,
+                        #child_deserialize_expr,
                     );
                 }
             }),
@@ -590,51 +922,100 @@ fn gen_child_match_arm(
     child_choice_enum_ident: &Type,
     gen_context: &GenContext,
     loop_children_suffix_match_set: &mut HashSet<String>,
+    borrowed: bool,
 ) -> Result<Arm, BuildErrorReport> {
     let child_type = gen_context
         .type_name_type_map
         .try_get(child.name.as_str())?;
 
-    let (_, child_prefixed_name) = child.split_name();
-    let (_, child_name) = child.split_last_name();
+    let (child_prefix, child_name) = child.split_last_name();
 
-    let child_prefixed_name_literal: LitByteStr =
-        parse_str(&format!("b\"{child_prefixed_name}\"")).map_err(BuildError::from)?;
-    let child_name_literal: LitByteStr =
-        parse_str(&format!("b\"{child_name}\"")).map_err(BuildError::from)?;
+    let child_name_pattern =
+        gen_qualified_name_pattern(child_prefix, child_name, false, gen_context)?;
+    let child_name_or_unbound_pattern =
+        gen_qualified_name_pattern(child_prefix, child_name, true, gen_context)?;
 
     let child_variant_name_ident = child.as_last_name_ident();
 
-    let child_variant_type: Type = parse_str(&format!(
-        "crate::schemas::{}::{}",
-        &child_type.module_name,
-        child_type.class_name.to_upper_camel_case()
-    ))
-    .map_err(BuildError::from)?;
+    let child_deserialize_expr = gen_child_deserialize_expr(child_type, gen_context, borrowed);
 
     if loop_children_suffix_match_set.insert(child_name.to_string()) {
         return Ok(parse_quote! {
-          #child_prefixed_name_literal | #child_name_literal => {
+          #child_name_or_unbound_pattern => {
             children.push(#child_choice_enum_ident::#child_variant_name_ident(std::boxed::Box::new(
-              #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?,
+              #child_deserialize_expr,
             )));
           }
         });
     };
 
     return Ok(parse_quote! {
-      #child_prefixed_name_literal => {
+      #child_name_pattern => {
         children.push(#child_choice_enum_ident::#child_variant_name_ident(std::boxed::Box::new(
-          #child_variant_type::deserialize_inner(xml_reader, Some((e, e_empty)))?,
+          #child_deserialize_expr,
         )));
       }
     });
 }
 
-fn gen_simple_child_match_arm(
+/// Generates the arms accumulating the text of a leaf text element, which may be split across
+/// text, CDATA and entity reference events.
+fn gen_text_match_arm_list(borrowed: bool) -> Vec<Arm> {
+    if borrowed {
+        return vec![
+            parse_quote! {
+              quick_xml::events::Event::Text(t) => {
+                crate::common::push_borrowed_text(
+                  &mut xml_text,
+                  t.decode().map_err(crate::common::SdkError::from)?,
+                );
+              }
+            },
+            parse_quote! {
+              quick_xml::events::Event::CData(t) => {
+                crate::common::push_borrowed_text(
+                  &mut xml_text,
+                  t.decode().map_err(crate::common::SdkError::from)?,
+                );
+              }
+            },
+            parse_quote! {
+              quick_xml::events::Event::GeneralRef(r) => {
+                crate::common::push_general_ref(xml_text.get_or_insert_default().to_mut(), &r)?;
+              }
+            },
+        ];
+    }
+
+    return vec![
+        parse_quote! {
+          quick_xml::events::Event::Text(t) => {
+            xml_text
+              .get_or_insert_with(String::new)
+              .push_str(&t.decode().map_err(crate::common::SdkError::from)?);
+          }
+        },
+        parse_quote! {
+          quick_xml::events::Event::CData(t) => {
+            xml_text
+              .get_or_insert_with(String::new)
+              .push_str(&t.decode().map_err(crate::common::SdkError::from)?);
+          }
+        },
+        parse_quote! {
+          quick_xml::events::Event::GeneralRef(r) => {
+            crate::common::push_general_ref(xml_text.get_or_insert_with(String::new), &r)?;
+          }
+        },
+    ];
+}
+
+/// Generates the statement parsing the accumulated text of a leaf text element into
+/// `xml_content`.
+fn gen_simple_content_stmt(
     first_name: &str,
     gen_context: &GenContext,
-) -> Result<Arm, BuildErrorReport> {
+) -> Result<Stmt, BuildErrorReport> {
     if let Some(schema_enum) = gen_context.enum_type_enum_map.get(first_name) {
         let simple_type_name: Type = parse_str(&format!(
             "crate::schemas::{}::{}",
@@ -644,9 +1025,14 @@ fn gen_simple_child_match_arm(
         .map_err(BuildError::from)?;
 
         return Ok(parse_quote! {
-          quick_xml::events::Event::Text(t) => {
-            xml_content = Some(#simple_type_name::from_bytes(&t.into_inner())?);
-          }
+          let xml_content = match xml_text {
+            Some(xml_text) => xml_reader.recover(
+              #simple_type_name::from_bytes(xml_text.as_bytes()),
+              None,
+              xml_text.as_bytes(),
+            )?,
+            None => None,
+          };
         });
     }
 
@@ -658,42 +1044,85 @@ fn gen_simple_child_match_arm(
     return Ok(parse2(match simple_type_str {
         "Base64BinaryValue" | "DateTimeValue" | "DecimalValue" | "HexBinaryValue"
         | "IntegerValue" | "SByteValue" | "StringValue" => quote! {
-          quick_xml::events::Event::Text(t) => {
-            xml_content = Some(t.decode().map_err(crate::common::SdkError::from)?.to_string());
-          }
+          let xml_content = xml_text;
         },
         "BooleanValue" | "OnOffValue" | "TrueFalseBlankValue" | "TrueFalseValue" => quote! {
-          quick_xml::events::Event::Text(t) => {
-            xml_content = Some(crate::common::parse_bool_bytes(&t.into_inner())?);
-          }
+          let xml_content = match xml_text {
+            Some(xml_text) => xml_reader.recover(
+              crate::common::parse_bool_bytes(xml_text.as_bytes()),
+              None,
+              xml_text.as_bytes(),
+            )?,
+            None => None,
+          };
         },
         "ByteValue" | "Int16Value" | "Int32Value" | "Int64Value" | "UInt16Value"
         | "UInt32Value" | "UInt64Value" | "DoubleValue" | "SingleValue" => quote! {
-          quick_xml::events::Event::Text(t) => {
-            xml_content = Some(
-              t.decode().map_err(crate::common::SdkError::from)?.parse::<#enum_type>().map_err(crate::common::SdkError::from)?
-            );
-          }
+          let xml_content = match xml_text {
+            Some(xml_text) => xml_reader.recover(
+              xml_text.parse::<#enum_type>().map_err(crate::common::SdkError::from),
+              None,
+              xml_text.as_bytes(),
+            )?,
+            None => None,
+          };
         },
         _ => unreachable!("{simple_type_str}"),
     })
     .map_err(BuildError::from)?);
 }
 
+/// Generates a pattern matching the `(namespace URI, local name)` pair of a resolved name. Names
+/// without a prefix only match unbound names, and `match_unbound` additionally accepts unbound
+/// names for prefixed ones.
+fn gen_qualified_name_pattern(
+    prefix: &str,
+    local_name: &str,
+    match_unbound: bool,
+    gen_context: &GenContext,
+) -> Result<TokenStream, BuildErrorReport> {
+    let local_name_literal: LitByteStr =
+        parse_str(&format!("b\"{local_name}\"")).map_err(BuildError::from)?;
+
+    if prefix.is_empty() {
+        return Ok(quote! { (None, #local_name_literal) });
+    }
+
+    let namespace_uri_literal: LitByteStr = parse_str(&format!(
+        "b\"{}\"",
+        gen_context.prefix_namespace_map.try_get(prefix)?.uri
+    ))
+    .map_err(BuildError::from)?;
+
+    if match_unbound {
+        return Ok(quote! { (Some(#namespace_uri_literal) | None, #local_name_literal) });
+    }
+
+    return Ok(quote! { (Some(#namespace_uri_literal), #local_name_literal) });
+}
+
 fn gen_field_match_arm(
     schema: &OpenXmlSchemaTypeAttribute,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<Arm, BuildErrorReport> {
     let attr_name_ident = schema.as_name_ident();
-    let attr_name_str = schema.as_name_str();
+    let attr_q_name_str = schema.q_name.trim_start_matches(':');
 
-    let attr_name_literal: LitByteStr =
-        parse_str(&format!("b\"{attr_name_str}\"")).map_err(BuildError::from)?;
+    let (attr_prefix, attr_name_str) = schema
+        .q_name
+        .split_once(':')
+        .unwrap_or(("", &schema.q_name));
+
+    let attr_name_pattern =
+        gen_qualified_name_pattern(attr_prefix, attr_name_str, false, gen_context)?;
+
+    let into_owned_opt: Option<TokenStream> = (!borrowed).then(|| quote! { .into_owned() });
 
     Ok(parse2(if schema.r#type.starts_with("ListValue<") {
         quote! {
-            #attr_name_literal => {
-                #attr_name_ident = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned());
+            #attr_name_pattern => {
+                #attr_name_ident = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)? #into_owned_opt);
             }
         }
     } else if schema.r#type.starts_with("EnumValue<") {
@@ -729,21 +1158,26 @@ fn gen_field_match_arm(
         .map_err(BuildError::from)?;
 
         quote! {
-          #attr_name_literal => {
-            #attr_name_ident = Some(#enum_type::from_bytes(&attr.value)?);
+          #attr_name_pattern => {
+            #attr_name_ident =
+              xml_reader.recover(#enum_type::from_bytes(&attr.value), Some(#attr_q_name_str), &attr.value)?;
           }
         }
     } else {
         match schema.r#type.as_str() {
           "Base64BinaryValue" | "DateTimeValue" | "DecimalValue" | "HexBinaryValue"
           | "IntegerValue" | "SByteValue" | "StringValue" => quote! {
-            #attr_name_literal => {
-              #attr_name_ident = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?.into_owned());
+            #attr_name_pattern => {
+              #attr_name_ident = Some(attr.decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)? #into_owned_opt);
             }
           },
           "BooleanValue" | "OnOffValue" | "TrueFalseBlankValue" | "TrueFalseValue" => quote! {
-            #attr_name_literal => {
-              #attr_name_ident = Some(crate::common::parse_bool_bytes(&attr.value)?);
+            #attr_name_pattern => {
+              #attr_name_ident = xml_reader.recover(
+                crate::common::parse_bool_bytes(&attr.value),
+                Some(#attr_q_name_str),
+                &attr.value,
+              )?;
             }
           },
           "ByteValue" | "Int16Value" | "Int32Value" | "Int64Value" | "UInt16Value" | "UInt32Value"
@@ -752,12 +1186,15 @@ fn gen_field_match_arm(
               parse_str(&format!("crate::common::simple_type::{}", &schema.r#type)).map_err(BuildError::from)?;
 
             quote! {
-              #attr_name_literal => {
-                #attr_name_ident = Some(
-                  attr
-                    .decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?
-                    .parse::<#enum_type>().map_err(crate::common::SdkError::from)?,
-                );
+              #attr_name_pattern => {
+                let decoded_value = attr
+                  .decode_and_unescape_value(xml_reader.decoder()).map_err(crate::common::SdkError::from)?;
+
+                #attr_name_ident = xml_reader.recover(
+                  decoded_value.parse::<#enum_type>().map_err(crate::common::SdkError::from),
+                  Some(#attr_q_name_str),
+                  &attr.value,
+                )?;
               }
             }
           }
@@ -25,6 +25,15 @@ pub fn gen_open_xml_parts(
         pub const RELATIONSHIP: &str = #relationship_type_str;
     };
 
+    let content_type_str = &part.content_type;
+    let content_type_impl_const: Option<ImplItemConst> = if content_type_str.is_empty() {
+        None
+    } else {
+        Some(parse_quote! {
+            pub const CONTENT_TYPE: &str = #content_type_str;
+        })
+    };
+
     let part_name_raw = part.name.as_str();
     let part_struct_name_ident: Ident = parse_str(&part_name_raw.to_upper_camel_case()).unwrap();
     let part_struct = gen_struct_fn(part, gen_context, &part_struct_name_ident)?;
@@ -38,17 +47,11 @@ pub fn gen_open_xml_parts(
     let mut field_declaration_list: Vec<Stmt> = vec![];
     let mut field_unwrap_list: Vec<Stmt> = vec![];
     let mut self_field_value_list: Vec<FieldValue> = vec![];
-    let mut children_stmt: Option<Stmt> = None;
     let mut children_arm_list: Vec<Arm> = vec![];
 
-    let part_rels_path_ident: Ident =
-        parse_str(&format!("{}_rels_path", part_name_raw.to_snake_case())).unwrap();
-
     if part.base == "OpenXmlPackage" {
-        field_declaration_list.push(parse_quote! {
-          let content_types = crate::common::opc_content_types::Types::from_reader(
-            std::io::BufReader::new(archive.by_name("[Content_Types].xml").map_err(SdkError::from)?,
-          ))?;
+        field_unwrap_list.push(parse_quote! {
+          let content_types = std::mem::take(&mut package_reader.content_types);
         });
 
         self_field_value_list.push(parse_quote! {
@@ -61,51 +64,29 @@ pub fn gen_open_xml_parts(
     }
 
     if !part.children.is_empty() {
-        field_declaration_list.push(parse_quote! {
-          let mut rels_path = "".to_string();
-        });
-
         field_declaration_list.push(parse_quote! {
           let child_parent_path = format!("{}{}", parent_path, #path_str);
         });
+    }
 
-        field_declaration_list.push(parse_quote! {
-          let part_target_str = if path.ends_with(".xml") {
-            path
-              .rsplit_once('/')
-              .ok_or_else(|| SdkError::CommonError(path.to_string()))?
-              .1
-          } else {
-            ""
-          };
-        });
-
-        field_declaration_list.push(parse_quote! {
-          let #part_rels_path_ident = resolve_zip_file_path(
-            &format!("{child_parent_path}_rels/{part_target_str}.rels"),
-          );
-        });
-
-        field_declaration_list.push(parse_quote! {
-            let relationships = if let Some(file_path) = file_path_set.get(&#part_rels_path_ident) {
-                rels_path = file_path.to_string();
+    field_declaration_list.push(parse_quote! {
+      let (part_dir_path, _) = split_part_path(path);
+    });
 
-                Some(crate::common::opc_relationships::Relationships::from_reader(
-                    std::io::BufReader::new(archive.by_name(file_path).map_err(SdkError::from)?)
-                )?)
-            } else {
-              None
-            };
-        });
+    field_declaration_list.push(parse_quote! {
+        let (rels_path, relationships) = match package_reader.read_relationships(path)? {
+            Some((rels_path, relationships)) => (rels_path, Some(relationships)),
+            None => ("".to_string(), None),
+        };
+    });
 
-        self_field_value_list.push(parse_quote! {
-            rels_path
-        });
+    self_field_value_list.push(parse_quote! {
+        rels_path
+    });
 
-        self_field_value_list.push(parse_quote! {
-            relationships
-        });
-    }
+    self_field_value_list.push(parse_quote! {
+        relationships
+    });
 
     self_field_value_list.push(parse_quote! {
         inner_path: path.to_string()
@@ -114,91 +95,32 @@ pub fn gen_open_xml_parts(
     match (part_name_raw, !part.extension.is_empty()) {
         ("CustomXmlPart" | "XmlSignaturePart", _) => {
             field_declaration_list.push(parse_quote! {
-                use std::io::Read;
+                let (part_content, encoding) =
+                    crate::common::decode_xml_bytes(&package_reader.read_entry(path)?)
+                        .map_err(|e| e.attach(format!("Reading part [{path}]")))?;
             });
 
-            field_declaration_list.push(parse_quote! {
-                let mut part_content = String::new();
-            });
-
-            field_declaration_list.push(
-                parse_quote! {
-                    {
-                        let mut file = std::io::BufReader::new(archive.by_name(path).map_err(SdkError::from)?);
-                        file.read_to_string(&mut part_content).map_err(SdkError::from)?;
-                    }
-                },
-            );
-
             self_field_value_list.push(parse_quote! {
                 part_content
             });
-        }
-        ("CustomDataPart" | "InternationalMacroSheetPart", _) | (_, true) => {
-            field_declaration_list.push(parse_quote! {
-                use std::io::Read;
-            });
-
-            field_declaration_list.push(parse_quote! {
-                let mut part_content;
-            });
-
-            field_declaration_list.push(parse_quote! {
-                {
-                    let mut zip_entry = archive.by_name(path).map_err(SdkError::from)?;
-
-                    part_content = Vec::with_capacity(zip_entry.size() as usize);
-
-                    zip_entry.read_to_end(&mut part_content).map_err(SdkError::from)?;
-                }
-            });
 
             self_field_value_list.push(parse_quote! {
-                part_content
+                encoding
             });
         }
-        ("CoreFilePropertiesPart", _) => {
+        ("CustomDataPart" | "InternationalMacroSheetPart", _) | (_, true) => {
             field_declaration_list.push(parse_quote! {
-                let root_element = Some(
-                    crate::common::opc_core_properties::CoreProperties::from_reader(
-                        std::io::BufReader::new(archive.by_name(path).map_err(SdkError::from)?)
-                    )?,
-                );
-            });
-
-            field_unwrap_list.push(parse_quote! {
-                let root_element = root_element
-                    .ok_or_else(|| SdkError::CommonError("root_element".to_string()))?;
+                let part_content = package_reader.read_entry(path)?;
             });
 
             self_field_value_list.push(parse_quote! {
-                root_element
+                part_content
             });
         }
         _ => {
-            if let Some(root_element_type_name) =
-                gen_context.part_name_type_name_map.get(part_name_raw)
-            {
-                let root_element_type = gen_context
-                    .type_name_type_map
-                    .try_get(root_element_type_name)?;
-
-                let field_type: Type = parse_str(&format!(
-                    "crate::schemas::{}::{}",
-                    root_element_type.module_name,
-                    root_element_type.class_name.to_upper_camel_case()
-                ))
-                .unwrap();
-
+            if let Some(field_type) = gen_root_element_type(part_name_raw, gen_context)? {
                 field_declaration_list.push(parse_quote! {
-                    let root_element = Some(#field_type::from_reader(
-                        std::io::BufReader::new(archive.by_name(path).map_err(SdkError::from)?)
-                    )?);
-                });
-
-                field_unwrap_list.push(parse_quote! {
-                    let root_element = root_element
-                        .ok_or_else(|| SdkError::CommonError("root_element".to_string()))?;
+                    let root_element = package_reader.read_root_element::<#field_type>(path)?;
                 });
 
                 self_field_value_list.push(parse_quote! {
@@ -209,10 +131,6 @@ pub fn gen_open_xml_parts(
     }
 
     for child in &part.children {
-        if child.is_data_part_reference {
-            continue;
-        }
-
         let child_api_name_str = child.api_name.to_snake_case();
         let child_api_name_ident: Ident = parse_str(&child_api_name_str).unwrap();
 
@@ -230,6 +148,28 @@ pub fn gen_open_xml_parts(
         ))
         .unwrap();
 
+        if child.is_data_part_reference {
+            field_declaration_list.push(parse_quote! {
+                let mut #child_api_name_ident: Vec<#child_type> = vec![];
+            });
+
+            children_arm_list.push(parse_quote! {
+                #relationship_type_ty if package_reader.contains(&target_path) => {
+                    #child_api_name_ident.push(#child_type::new_from_package_reader(
+                        &target_path,
+                        &relationship.id,
+                        package_reader,
+                    )?);
+                }
+            });
+
+            self_field_value_list.push(parse_quote! {
+                #child_api_name_ident
+            });
+
+            continue;
+        }
+
         if child.max_occurs_great_than_one {
             field_declaration_list.push(parse_quote! {
                 let mut #child_api_name_ident: Vec<#child_type> = vec![];
@@ -237,16 +177,11 @@ pub fn gen_open_xml_parts(
 
             children_arm_list.push(parse_quote! {
                 #relationship_type_ty => {
-                    let target_path = resolve_zip_file_path(
-                        &format!("{}{}", child_parent_path, relationship.target),
-                    );
-
-                    let #child_name_ident = #child_type::new_from_archive(
+                    let #child_name_ident = #child_type::new_from_package_reader(
                         &child_parent_path,
                         &target_path,
                         &relationship.id,
-                        file_path_set,
-                        archive,
+                        package_reader,
                     )?;
 
                     #child_api_name_ident.push(#child_name_ident);
@@ -259,16 +194,11 @@ pub fn gen_open_xml_parts(
 
             children_arm_list.push(parse_quote! {
                 #relationship_type_ty => {
-                    let target_path = resolve_zip_file_path(
-                        &format!("{}{}", child_parent_path, relationship.target),
-                    );
-
-                    #child_api_name_ident = Some(std::boxed::Box::new(#child_type::new_from_archive(
+                    #child_api_name_ident = Some(std::boxed::Box::new(#child_type::new_from_package_reader(
                         &child_parent_path,
                         &target_path,
                         &relationship.id,
-                        file_path_set,
-                        archive,
+                        package_reader,
                     )?));
                 }
             });
@@ -286,28 +216,59 @@ pub fn gen_open_xml_parts(
         });
     }
 
-    if !part.children.is_empty() {
-        children_stmt = Some(parse_quote! {
-            if let Some(relationships) = &relationships {
-                for relationship in &relationships.relationship {
-                    #[allow(clippy::single_match)]
-                    match relationship.r#type.as_str() {
-                        #( #children_arm_list, )*
-                        _ => ()
+    field_declaration_list.push(parse_quote! {
+        let mut unknown_parts: Vec<crate::parts::unknown_part::UnknownPart> = vec![];
+    });
+
+    self_field_value_list.push(parse_quote! {
+        unknown_parts
+    });
+
+    let children_stmt: Stmt = parse_quote! {
+        if let Some(relationships) = &relationships {
+            package_reader.ancestor_path_list.push(path.to_string());
+
+            for relationship in &relationships.relationship {
+                if let Some(crate::common::opc_relationships::TargetMode::External) = relationship.target_mode {
+                    continue;
+                }
+
+                let target_path = resolve_relationship_target(part_dir_path, &relationship.target);
+
+                if package_reader.ancestor_path_list.contains(&target_path) {
+                    continue;
+                }
+
+                match relationship.r#type.as_str() {
+                    #( #children_arm_list, )*
+                    _ if package_reader.contains(&target_path) => {
+                        unknown_parts.push(crate::parts::unknown_part::UnknownPart::new_from_package_reader(
+                            &target_path,
+                            relationship,
+                            package_reader,
+                        )?);
+                    }
+                    _ => {
+                        tracing::warn!(
+                            "Skipping relationship ({}) with missing target: ({})",
+                            relationship.id,
+                            target_path
+                        );
                     }
                 }
             }
-        });
-    }
 
-    let part_new_from_archive_fn: ItemFn = parse_quote! {
+            package_reader.ancestor_path_list.pop();
+        }
+    };
+
+    let part_new_from_package_reader_fn: ItemFn = parse_quote! {
         #[allow(unused_variables)]
-        pub(crate) fn new_from_archive<R: std::io::Read + std::io::Seek>(
+        pub(crate) fn new_from_package_reader(
             parent_path: &str,
             path: &str,
             r_id: &str,
-            file_path_set: &std::collections::HashSet<String>,
-            archive: &mut zip::ZipArchive<R>,
+            package_reader: &mut crate::parts::package_reader::PackageReader<'_>,
         ) -> Result<Self, SdkErrorReport> {
             #( #field_declaration_list )*
 
@@ -323,48 +284,112 @@ pub fn gen_open_xml_parts(
 
     let part_save_zip_fn = gen_save_zip_fn(part, gen_context, path_str)?;
 
+    let part_external_relationship_fns = gen_external_relationship_fns();
+
+    let part_child_part_fns = gen_child_part_fns(part, gen_context)?;
+
+    let package_create_fns = gen_package_create_fns(part, gen_context)?;
+
     let part_impl: ItemImpl = if part.base == "OpenXmlPackage" {
         let part_new_fn: ItemFn = parse_quote! {
-            pub fn new<R: std::io::Read + std::io::Seek>(
-                reader: R,
-            ) -> Result<Self, SdkErrorReport> {
+            /// Opens and parses the whole package. Every part is serialized again on save, use
+            /// [`Self::new_with_archive`] to copy unmodified parts verbatim instead.
+            pub fn new<R: std::io::Read + std::io::Seek>(reader: R) -> Result<Self, SdkErrorReport> {
                 let mut archive = zip::ZipArchive::new(reader).map_err(SdkError::from)?;
-                let mut file_path_set = std::collections::HashSet::with_capacity(archive.len());
 
-                for i in 0..archive.len() {
-                    let file = archive.by_index(i).map_err(SdkError::from)?;
-                    if let Some(path) = file.enclosed_name() {
-                        file_path_set.insert(path.to_string_lossy().into_owned());
-                    }
-                }
+                Self::new_from_package_reader(
+                    "",
+                    "",
+                    "",
+                    &mut crate::parts::package_reader::PackageReader::from_zip(&mut archive, None, false)?,
+                )
+            }
+        };
 
-                Self::new_from_archive("", "", "", &file_path_set, &mut archive)
+        let part_new_with_archive_fn: ItemFn = parse_quote! {
+            /// Opens and parses the whole package, keeping the archive open so that parts left
+            /// unmodified are copied verbatim on save.
+            pub fn new_with_archive<R: std::io::Read + std::io::Seek + Send + 'static>(
+                reader: R,
+            ) -> Result<Self, SdkErrorReport> {
+                let package_archive = crate::parts::package_archive::PackageArchive::new(reader)?;
+
+                let mut archive = package_archive.lock()?;
+
+                Self::new_from_package_reader(
+                    "",
+                    "",
+                    "",
+                    &mut crate::parts::package_reader::PackageReader::from_zip(
+                        &mut archive,
+                        Some(&package_archive),
+                        false,
+                    )?,
+                )
             }
         };
 
         let part_new_from_file_fn: ItemFn = parse_quote! {
+            /// Opens and parses the whole package like [`Self::new_with_archive`].
             pub fn new_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SdkErrorReport> {
-                Self::new(std::io::BufReader::new(std::fs::File::open(path).map_err(SdkError::from)?))
+                Self::new_with_archive(std::io::BufReader::new(std::fs::File::open(path).map_err(SdkError::from)?))
+            }
+        };
+
+        let part_new_lazy_fn: ItemFn = parse_quote! {
+            /// Opens the package while keeping the archive open, parsing each root element on
+            /// first access instead of up front.
+            pub fn new_lazy<R: std::io::Read + std::io::Seek + Send + 'static>(
+                reader: R,
+            ) -> Result<Self, SdkErrorReport> {
+                let package_archive = crate::parts::package_archive::PackageArchive::new(reader)?;
+
+                let mut archive = package_archive.lock()?;
+
+                Self::new_from_package_reader(
+                    "",
+                    "",
+                    "",
+                    &mut crate::parts::package_reader::PackageReader::from_zip(
+                        &mut archive,
+                        Some(&package_archive),
+                        true,
+                    )?,
+                )
+            }
+        };
+
+        let part_new_lazy_from_file_fn: ItemFn = parse_quote! {
+            pub fn new_lazy_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SdkErrorReport> {
+                Self::new_lazy(std::io::BufReader::new(std::fs::File::open(path).map_err(SdkError::from)?))
             }
         };
 
         let part_save_fn: ItemFn = parse_quote! {
             pub fn save<W: std::io::Write + std::io::Seek>(&self, writer: W) -> Result<(), SdkErrorReport> {
-                use std::io::Write;
+                self.save_with_options(writer, &crate::common::SaveOptions::default())
+            }
+        };
 
+        let part_save_with_options_fn: ItemFn = parse_quote! {
+            pub fn save_with_options<W: std::io::Write + std::io::Seek>(
+                &self,
+                writer: W,
+                save_options: &crate::common::SaveOptions,
+            ) -> Result<(), SdkErrorReport> {
                 let mut entry_set: std::collections::HashSet<String> = std::collections::HashSet::new();
 
                 let mut zip = zip::ZipWriter::new(writer);
 
-                let options = zip::write::SimpleFileOptions::default()
-                  .compression_method(zip::CompressionMethod::Deflated)
-                  .unix_permissions(0o755);
+                let options = save_options.zip_file_options();
 
                 zip.start_file("[Content_Types].xml", options).map_err(SdkError::from)?;
 
-                zip.write_all(&self.content_types.to_xml_bytes(true, false)).map_err(SdkError::from)?;
+                self.content_types
+                  .write_xml_with_options(&mut zip, true, true, &save_options.serialize_options)
+                  .map_err(SdkError::from)?;
 
-                self.save_zip("", &mut zip, &mut entry_set)?;
+                self.save_zip("", &mut zip, &mut entry_set, save_options)?;
 
                 zip.finish().map_err(SdkError::from)?;
 
@@ -378,21 +403,88 @@ pub fn gen_open_xml_parts(
             }
         };
 
+        let flat_opc_progid: TokenStream = match part.name.as_str() {
+            "WordprocessingDocument" => quote! { Some("Word.Document") },
+            "PresentationDocument" => quote! { Some("PowerPoint.Show") },
+            _ => quote! { None },
+        };
+
+        let part_new_from_flat_opc_fn: ItemFn = parse_quote! {
+            /// Opens a package stored as a single Flat OPC XML document.
+            pub fn new_from_flat_opc<R: std::io::Read>(reader: R) -> Result<Self, SdkErrorReport> {
+                Self::new(std::io::Cursor::new(crate::parts::flat_opc::flat_opc_to_zip(reader)?))
+            }
+        };
+
+        let part_save_flat_opc_fn: ItemFn = parse_quote! {
+            /// Saves the package as a single Flat OPC XML document.
+            pub fn save_flat_opc<W: std::io::Write>(&self, writer: W) -> Result<(), SdkErrorReport> {
+                let mut buffer = std::io::Cursor::new(Vec::new());
+
+                self.save(&mut buffer)?;
+
+                crate::parts::flat_opc::zip_to_flat_opc(buffer, writer, #flat_opc_progid)
+            }
+        };
+
+        let part_new_from_dir_fn: ItemFn = parse_quote! {
+            /// Opens a package extracted into a directory.
+            pub fn new_from_dir<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SdkErrorReport> {
+                Self::new(std::io::Cursor::new(crate::parts::package_dir::dir_to_zip(path)?))
+            }
+        };
+
+        let part_save_to_dir_fn: ItemFn = parse_quote! {
+            /// Saves the package extracted into a directory, overwriting existing files but
+            /// leaving files of other parts in place.
+            pub fn save_to_dir<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), SdkErrorReport> {
+                let mut buffer = std::io::Cursor::new(Vec::new());
+
+                self.save(&mut buffer)?;
+
+                crate::parts::package_dir::zip_to_dir(buffer, path)
+            }
+        };
+
         parse_quote! {
             impl #part_struct_name_ident {
                 #relationship_type_impl_const
 
+                #content_type_impl_const
+
+                #( #package_create_fns )*
+
                 #part_new_fn
 
+                #part_new_with_archive_fn
+
                 #part_new_from_file_fn
 
-                #part_new_from_archive_fn
+                #part_new_lazy_fn
+
+                #part_new_lazy_from_file_fn
+
+                #part_new_from_flat_opc_fn
+
+                #part_new_from_dir_fn
+
+                #part_new_from_package_reader_fn
 
                 #part_save_fn
 
+                #part_save_with_options_fn
+
                 #part_save_to_file_fn
 
+                #part_save_flat_opc_fn
+
+                #part_save_to_dir_fn
+
                 #part_save_zip_fn
+
+                #part_external_relationship_fns
+
+                #( #part_child_part_fns )*
             }
         }
     } else {
@@ -400,9 +492,15 @@ pub fn gen_open_xml_parts(
             impl #part_struct_name_ident {
                 #relationship_type_impl_const
 
-                #part_new_from_archive_fn
+                #content_type_impl_const
+
+                #part_new_from_package_reader_fn
 
                 #part_save_zip_fn
+
+                #part_external_relationship_fns
+
+                #( #part_child_part_fns )*
             }
         }
     };
@@ -436,15 +534,13 @@ fn gen_struct_fn(
         });
     }
 
-    if !part.children.is_empty() {
-        fields.push(quote! {
-            pub relationships: Option<crate::common::opc_relationships::Relationships>,
-        });
+    fields.push(quote! {
+        pub relationships: Option<crate::common::opc_relationships::Relationships>,
+    });
 
-        fields.push(quote! {
-            pub rels_path: String,
-        });
-    }
+    fields.push(quote! {
+        pub rels_path: String,
+    });
 
     fields.push(quote! {
         pub inner_path: String,
@@ -453,30 +549,17 @@ fn gen_struct_fn(
     fields.push(match (part_name_raw, !part.extension.is_empty()) {
         ("CustomXmlPart" | "XmlSignaturePart", _) => quote! {
             pub part_content: String,
+            /// The encoding the part content was read in, written again on save if
+            /// [`crate::common::SaveOptions::keep_encoding`] is set.
+            pub encoding: crate::common::XmlEncoding,
         },
         ("CustomDataPart" | "InternationalMacroSheetPart", _) | (_, true) => quote! {
             pub part_content: Vec<u8>,
         },
-        ("CoreFilePropertiesPart", _) => quote! {
-            pub root_element: crate::common::opc_core_properties::CoreProperties,
-        },
         _ => {
-            if let Some(root_element_type_name) =
-                gen_context.part_name_type_name_map.get(part_name_raw)
-            {
-                let root_element_type = gen_context
-                    .type_name_type_map
-                    .try_get(root_element_type_name)?;
-
-                let field_type: Type = parse_str(&format!(
-                    "crate::schemas::{}::{}",
-                    root_element_type.module_name,
-                    root_element_type.class_name.to_upper_camel_case()
-                ))
-                .unwrap();
-
+            if let Some(field_type) = gen_root_element_type(part_name_raw, gen_context)? {
                 quote! {
-                    pub root_element: #field_type,
+                    pub root_element: crate::parts::part_root_element::PartRootElement<#field_type>,
                 }
             } else {
                 quote! {}
@@ -485,10 +568,6 @@ fn gen_struct_fn(
     });
 
     for child in &part.children {
-        if child.is_data_part_reference {
-            continue;
-        }
-
         let child_name_ident: Ident = parse_str(&child.api_name.to_snake_case()).unwrap();
 
         let child_type: Type = parse_str(&format!(
@@ -498,6 +577,14 @@ fn gen_struct_fn(
         ))
         .unwrap();
 
+        if child.is_data_part_reference {
+            fields.push(quote! {
+                pub #child_name_ident: Vec<#child_type>,
+            });
+
+            continue;
+        }
+
         fields.push(match child.as_occurrence() {
             Occurrence::Required => quote! {
                 pub #child_name_ident: std::boxed::Box<#child_type>,
@@ -511,6 +598,10 @@ fn gen_struct_fn(
         });
     }
 
+    fields.push(quote! {
+        pub unknown_parts: Vec<crate::parts::unknown_part::UnknownPart>,
+    });
+
     return Ok(parse_quote! {
         #[derive(Clone, Debug, Default)]
         pub struct #struct_name_ident {
@@ -519,6 +610,34 @@ fn gen_struct_fn(
     });
 }
 
+fn gen_root_element_type(
+    part_name: &str,
+    gen_context: &GenContext,
+) -> Result<Option<Type>, BuildErrorReport> {
+    if part_name == "CoreFilePropertiesPart" {
+        return Ok(Some(parse_quote! {
+            crate::common::opc_core_properties::CoreProperties
+        }));
+    }
+
+    let Some(root_element_type_name) = gen_context.part_name_type_name_map.get(part_name) else {
+        return Ok(None);
+    };
+
+    let root_element_type = gen_context
+        .type_name_type_map
+        .try_get(root_element_type_name)?;
+
+    let field_type: Type = parse_str(&format!(
+        "crate::schemas::{}::{}",
+        root_element_type.module_name,
+        root_element_type.class_name.to_upper_camel_case()
+    ))
+    .context_transform(BuildError::from)?;
+
+    return Ok(Some(field_type));
+}
+
 fn gen_save_zip_fn(
     part: &OpenXmlPart,
     gen_context: &GenContext,
@@ -535,9 +654,7 @@ fn gen_save_zip_fn(
 
     writer_list.push(
         quote! {
-            let options = zip::write::SimpleFileOptions::default()
-                .compression_method(zip::CompressionMethod::Deflated)
-                .unix_permissions(0o755);
+            let options = save_options.zip_file_options();
 
             let directory_path = resolve_zip_file_path(parent_path);
 
@@ -573,7 +690,12 @@ fn gen_save_zip_fn(
                 if !entry_set.contains(&self.inner_path) {
                     zip.start_file(&self.inner_path, options).map_err(SdkError::from)?;
 
-                    zip.write_all(self.part_content.as_bytes()).map_err(SdkError::from)?;
+                    if save_options.keep_encoding {
+                        zip.write_all(&crate::common::encode_xml_string(&self.part_content, self.encoding))
+                            .map_err(SdkError::from)?;
+                    } else {
+                        zip.write_all(self.part_content.as_bytes()).map_err(SdkError::from)?;
+                    }
 
                     entry_set.insert(self.inner_path.to_string());
                 }
@@ -590,19 +712,12 @@ fn gen_save_zip_fn(
                 }
             },
             ("CoreFilePropertiesPart", _, _) | (_, _, true) => quote! {
-                use std::io::Write;
-
                 if !entry_set.contains(&self.inner_path) {
-                    zip.start_file(&self.inner_path, options).map_err(SdkError::from)?;
-
-                    zip.write_all(&self.root_element.to_xml_bytes(true, false)).map_err(SdkError::from)?;
+                    self.root_element.save_zip_entry(zip, &self.inner_path, options, save_options)?;
 
                     entry_set.insert(self.inner_path.to_string());
                 }
             },
-            _ if !part.children.is_empty() => quote! {
-                use std::io::Write;
-            },
             _ => quote! {},
         },
     );
@@ -610,45 +725,60 @@ fn gen_save_zip_fn(
     if !part.children.is_empty() {
         writer_list.push(quote! {
             let child_parent_path = format!("{}{}", parent_path, #path_str);
+        });
+    }
 
-            if let Some(relationships) = &self.relationships {
-                let rels_dir_path = resolve_zip_file_path(
-                    &format!("{child_parent_path}_rels"),
-                );
+    writer_list.push(quote! {
+        if let Some(relationships) = &self.relationships {
+            let (part_dir_path, _) = split_part_path(&self.inner_path);
 
-                if !rels_dir_path.is_empty() && !entry_set.contains(&rels_dir_path) {
-                    zip.add_directory(&rels_dir_path, options).map_err(SdkError::from)?;
+            let rels_dir_path = resolve_zip_file_path(
+                &format!("{part_dir_path}_rels"),
+            );
 
-                    entry_set.insert(rels_dir_path);
-                }
+            if !rels_dir_path.is_empty() && !entry_set.contains(&rels_dir_path) {
+                zip.add_directory(&rels_dir_path, options).map_err(SdkError::from)?;
 
-                if !entry_set.contains(&self.rels_path) {
-                    zip.start_file(&self.rels_path, options).map_err(SdkError::from)?;
+                entry_set.insert(rels_dir_path);
+            }
 
-                    zip.write_all(&relationships.to_xml_bytes(true, false)).map_err(SdkError::from)?;
+            if !entry_set.contains(&self.rels_path) {
+                zip.start_file(&self.rels_path, options).map_err(SdkError::from)?;
 
-                    entry_set.insert(self.rels_path.to_string());
-                }
+                relationships
+                  .write_xml_with_options(&mut *zip, true, true, &save_options.serialize_options)
+                  .map_err(SdkError::from)?;
+
+                entry_set.insert(self.rels_path.to_string());
             }
-        });
-    }
+        }
+    });
 
     let mut children_writer_stmt_list: Vec<Stmt> = vec![];
     for child in &part.children {
+        let child_api_name_ident: Ident =
+            parse_str(&child.api_name.to_snake_case()).context_transform(BuildError::from)?;
+
         if child.is_data_part_reference {
+            let child_name_ident: Ident =
+                parse_str(&child.name.to_snake_case()).context_transform(BuildError::from)?;
+
+            children_writer_stmt_list.push(parse_quote! {
+                for #child_name_ident in &self.#child_api_name_ident {
+                    #child_name_ident.save_zip(zip, entry_set, save_options)?;
+                }
+            });
+
             continue;
         }
 
-        let child_api_name_ident: Ident =
-            parse_str(&child.api_name.to_snake_case()).context_transform(BuildError::from)?;
-
         let tokens = match child.as_occurrence() {
             Occurrence::Required => quote! {
-                self.#child_api_name_ident.save_zip(&child_parent_path, zip, entry_set)?;
+                self.#child_api_name_ident.save_zip(&child_parent_path, zip, entry_set, save_options)?;
             },
             Occurrence::Optional => quote! {
                 if let Some(#child_api_name_ident) = &self.#child_api_name_ident {
-                    #child_api_name_ident.save_zip(&child_parent_path, zip, entry_set)?;
+                    #child_api_name_ident.save_zip(&child_parent_path, zip, entry_set, save_options)?;
                 }
             },
             Occurrence::Repeated => {
@@ -657,7 +787,7 @@ fn gen_save_zip_fn(
 
                 quote! {
                     for #child_name_ident in &self.#child_api_name_ident {
-                        #child_name_ident.save_zip(&child_parent_path, zip, entry_set)?;
+                        #child_name_ident.save_zip(&child_parent_path, zip, entry_set, save_options)?;
                     }
                 }
             }
@@ -667,17 +797,723 @@ fn gen_save_zip_fn(
     }
 
     return Ok(parse_quote! {
+        #[allow(unused_variables)]
         pub(crate) fn save_zip<W: std::io::Write + std::io::Seek>(
             &self,
             parent_path: &str,
             zip: &mut zip::ZipWriter<W>,
             entry_set: &mut std::collections::HashSet<String>,
+            save_options: &crate::common::SaveOptions,
         ) -> Result<(), SdkErrorReport> {
             #( #writer_list )*
 
             #( #children_writer_stmt_list )*
 
+            for unknown_part in &self.unknown_parts {
+                unknown_part.save_zip(zip, entry_set, save_options)?;
+            }
+
             Ok(())
         }
     });
 }
+
+fn gen_package_create_fns(
+    part: &OpenXmlPart,
+    gen_context: &GenContext,
+) -> Result<Vec<ItemFn>, BuildErrorReport> {
+    let part_type = |part_name: &str| -> Result<Type, BuildErrorReport> {
+        return parse_str(&format!(
+            "crate::parts::{}::{}",
+            part_name.to_snake_case(),
+            part_name.to_upper_camel_case()
+        ))
+        .context_transform(BuildError::from);
+    };
+
+    let root_element_type = |part_name: &str| -> Result<Type, BuildErrorReport> {
+        return gen_root_element_type(part_name, gen_context)?
+            .ok_or_else(|| BuildError::HashMapExpectedSomeError(part_name.to_string()).into());
+    };
+
+    let core_file_properties_part_type = part_type("CoreFilePropertiesPart")?;
+    let core_properties_type = root_element_type("CoreFilePropertiesPart")?;
+    let core_properties_str = include_str!("../includes/templates/core.xml");
+
+    let (main_part_stmt_list, main_part_field_str, document_type_list): (
+        Vec<Stmt>,
+        &str,
+        Vec<(&str, &str)>,
+    ) = match part.name.as_str() {
+        "WordprocessingDocument" => {
+            let main_document_part_type = part_type("MainDocumentPart")?;
+            let document_type = root_element_type("MainDocumentPart")?;
+            let document_str = include_str!("../includes/templates/wordprocessing/document.xml");
+
+            let style_definitions_part_type = part_type("StyleDefinitionsPart")?;
+            let styles_type = root_element_type("StyleDefinitionsPart")?;
+            let styles_str = include_str!("../includes/templates/wordprocessing/styles.xml");
+
+            (
+                vec![
+                    parse_quote! {
+                        package.add_main_document_part(main_part_content_type, #main_document_part_type {
+                            root_element: #document_type::from_str(#document_str)?.into(),
+                            ..Default::default()
+                        });
+                    },
+                    parse_quote! {
+                        package.main_document_part.add_style_definitions_part(
+                            &mut package.content_types,
+                            #style_definitions_part_type {
+                                root_element: #styles_type::from_str(#styles_str)?.into(),
+                                ..Default::default()
+                            },
+                        );
+                    },
+                ],
+                "main_document_part",
+                vec![
+                    (
+                        "Document",
+                        "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml",
+                    ),
+                    (
+                        "Template",
+                        "application/vnd.openxmlformats-officedocument.wordprocessingml.template.main+xml",
+                    ),
+                    (
+                        "MacroEnabledDocument",
+                        "application/vnd.ms-word.document.macroEnabled.main+xml",
+                    ),
+                    (
+                        "MacroEnabledTemplate",
+                        "application/vnd.ms-word.template.macroEnabledTemplate.main+xml",
+                    ),
+                ],
+            )
+        }
+        "SpreadsheetDocument" => {
+            let workbook_part_type = part_type("WorkbookPart")?;
+            let workbook_type = root_element_type("WorkbookPart")?;
+            let workbook_str = include_str!("../includes/templates/spreadsheet/workbook.xml");
+
+            let worksheet_part_type = part_type("WorksheetPart")?;
+            let worksheet_type = root_element_type("WorksheetPart")?;
+            let worksheet_str = include_str!("../includes/templates/spreadsheet/sheet.xml");
+
+            let workbook_styles_part_type = part_type("WorkbookStylesPart")?;
+            let stylesheet_type = root_element_type("WorkbookStylesPart")?;
+            let stylesheet_str = include_str!("../includes/templates/spreadsheet/styles.xml");
+
+            (
+                vec![
+                    parse_quote! {
+                        package.add_workbook_part(main_part_content_type, #workbook_part_type {
+                            root_element: #workbook_type::from_str(#workbook_str)?.into(),
+                            ..Default::default()
+                        });
+                    },
+                    parse_quote! {
+                        package.workbook_part.add_worksheet_part(
+                            &mut package.content_types,
+                            #worksheet_part_type {
+                                root_element: #worksheet_type::from_str(#worksheet_str)?.into(),
+                                ..Default::default()
+                            },
+                        );
+                    },
+                    parse_quote! {
+                        package.workbook_part.add_workbook_styles_part(
+                            &mut package.content_types,
+                            #workbook_styles_part_type {
+                                root_element: #stylesheet_type::from_str(#stylesheet_str)?.into(),
+                                ..Default::default()
+                            },
+                        );
+                    },
+                ],
+                "workbook_part",
+                vec![
+                    (
+                        "Document",
+                        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml",
+                    ),
+                    (
+                        "Template",
+                        "application/vnd.openxmlformats-officedocument.spreadsheetml.template.main+xml",
+                    ),
+                    (
+                        "MacroEnabledDocument",
+                        "application/vnd.ms-excel.sheet.macroEnabled.main+xml",
+                    ),
+                    (
+                        "MacroEnabledTemplate",
+                        "application/vnd.ms-excel.template.macroEnabled.main+xml",
+                    ),
+                    (
+                        "AddIn",
+                        "application/vnd.ms-excel.addin.macroEnabled.main+xml",
+                    ),
+                ],
+            )
+        }
+        "PresentationDocument" => {
+            let presentation_part_type = part_type("PresentationPart")?;
+            let presentation_type = root_element_type("PresentationPart")?;
+            let presentation_str =
+                include_str!("../includes/templates/presentation/presentation.xml");
+
+            let slide_master_part_type = part_type("SlideMasterPart")?;
+            let slide_master_type = root_element_type("SlideMasterPart")?;
+            let slide_master_str =
+                include_str!("../includes/templates/presentation/slide_master.xml");
+
+            let slide_layout_part_type = part_type("SlideLayoutPart")?;
+            let slide_layout_type = root_element_type("SlideLayoutPart")?;
+            let slide_layout_str =
+                include_str!("../includes/templates/presentation/slide_layout.xml");
+
+            let theme_part_type = part_type("ThemePart")?;
+            let theme_type = root_element_type("ThemePart")?;
+            let theme_str = include_str!("../includes/templates/presentation/theme.xml");
+
+            (
+                vec![
+                    parse_quote! {
+                        package.add_presentation_part(main_part_content_type, #presentation_part_type {
+                            root_element: #presentation_type::from_str(#presentation_str)?.into(),
+                            ..Default::default()
+                        });
+                    },
+                    parse_quote! {
+                        let slide_master_part = package.presentation_part.add_slide_master_part(
+                            &mut package.content_types,
+                            #slide_master_part_type {
+                                root_element: #slide_master_type::from_str(#slide_master_str)?.into(),
+                                ..Default::default()
+                            },
+                        );
+                    },
+                    parse_quote! {
+                        let slide_master_target = format!(
+                            "../slideMasters/{}",
+                            split_part_path(&slide_master_part.inner_path).1,
+                        );
+                    },
+                    parse_quote! {
+                        let slide_layout_part = slide_master_part.add_slide_layout_part(
+                            &mut package.content_types,
+                            #slide_layout_part_type {
+                                root_element: #slide_layout_type::from_str(#slide_layout_str)?.into(),
+                                ..Default::default()
+                            },
+                        );
+                    },
+                    parse_quote! {
+                        slide_layout_part.rels_path = resolve_rels_path(&slide_layout_part.inner_path);
+                    },
+                    parse_quote! {
+                        slide_layout_part
+                            .relationships
+                            .get_or_insert_with(crate::common::opc_relationships::Relationships::new)
+                            .add_relationship(
+                                #slide_master_part_type::RELATIONSHIP,
+                                &slide_master_target,
+                                None,
+                            );
+                    },
+                    parse_quote! {
+                        slide_master_part.add_theme_part(
+                            &mut package.content_types,
+                            #theme_part_type {
+                                root_element: #theme_type::from_str(#theme_str)?.into(),
+                                ..Default::default()
+                            },
+                        );
+                    },
+                ],
+                "presentation_part",
+                vec![
+                    (
+                        "Document",
+                        "application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml",
+                    ),
+                    (
+                        "Template",
+                        "application/vnd.openxmlformats-officedocument.presentationml.template.main+xml",
+                    ),
+                    (
+                        "MacroEnabledDocument",
+                        "application/vnd.ms-powerpoint.presentation.macroEnabled.main+xml",
+                    ),
+                    (
+                        "MacroEnabledTemplate",
+                        "application/vnd.ms-powerpoint.template.macroEnabled.main+xml",
+                    ),
+                    (
+                        "Slideshow",
+                        "application/vnd.openxmlformats-officedocument.presentationml.slideshow.main+xml",
+                    ),
+                    (
+                        "MacroEnabledSlideshow",
+                        "application/vnd.ms-powerpoint.slideshow.macroEnabled.main+xml",
+                    ),
+                    (
+                        "AddIn",
+                        "application/vnd.ms-powerpoint.addin.macroEnabled.main+xml",
+                    ),
+                ],
+            )
+        }
+        _ => return Ok(vec![]),
+    };
+
+    let main_part_field_ident: Ident = parse_str(main_part_field_str).unwrap();
+
+    let mut document_type_ident_list: Vec<Ident> = vec![];
+    let mut content_type_str_list: Vec<&str> = vec![];
+
+    for (document_type_str, content_type_str) in document_type_list {
+        document_type_ident_list.push(parse_str(document_type_str).unwrap());
+        content_type_str_list.push(content_type_str);
+    }
+
+    return Ok(vec![
+        parse_quote! {
+            /// Creates a blank package holding the minimal set of parts.
+            pub fn create() -> Result<Self, SdkErrorReport> {
+                Self::create_with_document_type(crate::parts::document_type::DocumentType::Document)
+            }
+        },
+        parse_quote! {
+            /// Creates a blank template package holding the minimal set of parts.
+            pub fn create_template() -> Result<Self, SdkErrorReport> {
+                Self::create_with_document_type(crate::parts::document_type::DocumentType::Template)
+            }
+        },
+        parse_quote! {
+            /// Creates a blank macro-enabled package holding the minimal set of parts.
+            pub fn create_macro_enabled() -> Result<Self, SdkErrorReport> {
+                Self::create_with_document_type(
+                    crate::parts::document_type::DocumentType::MacroEnabledDocument,
+                )
+            }
+        },
+        parse_quote! {
+            /// Creates a blank macro-enabled template package holding the minimal set of parts.
+            pub fn create_macro_enabled_template() -> Result<Self, SdkErrorReport> {
+                Self::create_with_document_type(
+                    crate::parts::document_type::DocumentType::MacroEnabledTemplate,
+                )
+            }
+        },
+        parse_quote! {
+            /// Creates a blank package of the given document type holding the minimal set of parts.
+            pub fn create_with_document_type(
+                document_type: crate::parts::document_type::DocumentType,
+            ) -> Result<Self, SdkErrorReport> {
+                let main_part_content_type = Self::main_part_content_type(document_type)?;
+
+                let mut package = Self {
+                    content_types: crate::common::opc_content_types::Types::new(),
+                    ..Default::default()
+                };
+
+                #( #main_part_stmt_list )*
+
+                package.add_core_file_properties_part(#core_file_properties_part_type {
+                    root_element: #core_properties_type::from_str(#core_properties_str)?.into(),
+                    ..Default::default()
+                });
+
+                Ok(package)
+            }
+        },
+        parse_quote! {
+            /// Returns the main part content type of the given document type, failing if this kind
+            /// of package does not support it.
+            pub fn main_part_content_type(
+                document_type: crate::parts::document_type::DocumentType,
+            ) -> Result<&'static str, SdkErrorReport> {
+                match document_type {
+                    #( crate::parts::document_type::DocumentType::#document_type_ident_list => Ok(#content_type_str_list), )*
+                    #[allow(unreachable_patterns)]
+                    _ => Err(SdkError::CommonError(format!("unsupported document type: {document_type:?}")).into()),
+                }
+            }
+        },
+        parse_quote! {
+            /// Detects the document type from the content type of the main part, if it is known.
+            pub fn document_type(&self) -> Option<crate::parts::document_type::DocumentType> {
+                match self.content_types.get_content_type(&self.#main_part_field_ident.inner_path)? {
+                    #( #content_type_str_list => Some(crate::parts::document_type::DocumentType::#document_type_ident_list), )*
+                    _ => None,
+                }
+            }
+        },
+        parse_quote! {
+            /// Changes the document type by rewriting the content type of the main part.
+            ///
+            /// Changing to a document type without macros deletes the VBA project part.
+            pub fn set_document_type(
+                &mut self,
+                document_type: crate::parts::document_type::DocumentType,
+            ) -> Result<(), SdkErrorReport> {
+                let main_part_content_type = Self::main_part_content_type(document_type)?;
+
+                self.content_types
+                    .add_override(&self.#main_part_field_ident.inner_path, main_part_content_type);
+
+                if !document_type.is_macro_enabled() {
+                    self.#main_part_field_ident.delete_vba_project_part(&mut self.content_types);
+                }
+
+                Ok(())
+            }
+        },
+    ]);
+}
+
+fn gen_child_part_fns(
+    part: &OpenXmlPart,
+    gen_context: &GenContext,
+) -> Result<Vec<ItemFn>, BuildErrorReport> {
+    let mut child_part_fns: Vec<ItemFn> = vec![];
+
+    let (content_types_param, content_types_expr): (Option<TokenStream>, TokenStream) =
+        if part.base == "OpenXmlPackage" {
+            (None, quote! { self.content_types })
+        } else {
+            (
+                Some(quote! {
+                    content_types: &mut crate::common::opc_content_types::Types,
+                }),
+                quote! { content_types },
+            )
+        };
+
+    for child in &part.children {
+        if child.is_data_part_reference {
+            continue;
+        }
+
+        let occurrence = child.as_occurrence();
+
+        let child_part = gen_context.part_name_part_map.try_get(&child.name)?;
+
+        let child_api_name_ident: Ident =
+            parse_str(&child.api_name.to_snake_case()).context_transform(BuildError::from)?;
+
+        let child_name_str = child.name.to_snake_case();
+        let child_name_ident: Ident =
+            parse_str(&child_name_str).context_transform(BuildError::from)?;
+
+        let child_type: Type = parse_str(&format!(
+            "crate::parts::{child_name_str}::{}",
+            child.name.to_upper_camel_case()
+        ))
+        .context_transform(BuildError::from)?;
+
+        let add_fn_ident: Ident =
+            parse_str(&format!("add_{child_name_str}")).context_transform(BuildError::from)?;
+        let delete_fn_ident: Ident =
+            parse_str(&format!("delete_{child_name_str}")).context_transform(BuildError::from)?;
+
+        let target_dir_str = if child_part.paths.general == "." {
+            "".to_string()
+        } else {
+            format!("{}/", child_part.paths.general)
+        };
+
+        let target_str = &child_part.target;
+
+        let extension_str = if child_part.extension.is_empty() {
+            ".xml"
+        } else {
+            child_part.extension.as_str()
+        };
+
+        let (content_type_param, content_type_expr): (Option<TokenStream>, TokenStream) =
+            if child_part.content_type.is_empty() {
+                (
+                    Some(quote! { content_type: &str, }),
+                    quote! { content_type },
+                )
+            } else {
+                (None, quote! { #child_type::CONTENT_TYPE })
+            };
+
+        let numbered = matches!(occurrence, Occurrence::Repeated);
+
+        let is_taken_expr: TokenStream = match occurrence {
+            Occurrence::Required => quote! {
+                |_| false
+            },
+            Occurrence::Optional => quote! {
+                |path| {
+                    self.#child_api_name_ident
+                        .as_ref()
+                        .is_some_and(|child| child.inner_path == path)
+                }
+            },
+            Occurrence::Repeated => quote! {
+                |path| self.#child_api_name_ident.iter().any(|child| child.inner_path == path)
+            },
+        };
+
+        let wire_stmt_list: Vec<Stmt> = vec![
+            parse_quote! {
+                let (part_dir_path, _) = split_part_path(&self.inner_path);
+            },
+            parse_quote! {
+                let inner_path = #content_types_expr.next_part_name(
+                    &format!("{part_dir_path}{}", #target_dir_str),
+                    #target_str,
+                    #extension_str,
+                    #numbered,
+                    #is_taken_expr,
+                );
+            },
+            parse_quote! {
+                #content_types_expr.add_override(&inner_path, #content_type_expr);
+            },
+            parse_quote! {
+                if self.rels_path.is_empty() {
+                    self.rels_path = resolve_rels_path(&self.inner_path);
+                }
+            },
+            parse_quote! {
+                #child_name_ident.r_id = self
+                    .relationships
+                    .get_or_insert_with(crate::common::opc_relationships::Relationships::new)
+                    .add_relationship(
+                        #child_type::RELATIONSHIP,
+                        &format!("{}{}", #target_dir_str, split_part_path(&inner_path).1),
+                        None,
+                    );
+            },
+            parse_quote! {
+                #child_name_ident.rels_path = if #child_name_ident.relationships.is_some() {
+                    resolve_rels_path(&inner_path)
+                } else {
+                    "".to_string()
+                };
+            },
+            parse_quote! {
+                #child_name_ident.inner_path = inner_path;
+            },
+        ];
+
+        if let Occurrence::Required = occurrence {
+            child_part_fns.push(parse_quote! {
+                /// Replaces the part, dropping the relationship and content type registration
+                /// of the current one.
+                pub fn #add_fn_ident(
+                    &mut self,
+                    #content_types_param
+                    #content_type_param
+                    mut #child_name_ident: #child_type,
+                ) -> &mut #child_type {
+                    if let Some(relationships) = &mut self.relationships {
+                        relationships.remove_relationship(&self.#child_api_name_ident.r_id);
+                    }
+
+                    #content_types_expr.remove_override(&self.#child_api_name_ident.inner_path);
+
+                    #( #wire_stmt_list )*
+
+                    *self.#child_api_name_ident = #child_name_ident;
+
+                    &mut self.#child_api_name_ident
+                }
+            });
+        } else if numbered {
+            child_part_fns.push(parse_quote! {
+                /// Adds the part under a free part name, registering its content type and a
+                /// relationship with a new id.
+                pub fn #add_fn_ident(
+                    &mut self,
+                    #content_types_param
+                    #content_type_param
+                    mut #child_name_ident: #child_type,
+                ) -> &mut #child_type {
+                    #( #wire_stmt_list )*
+
+                    self.#child_api_name_ident.push(#child_name_ident);
+
+                    self.#child_api_name_ident.last_mut().unwrap()
+                }
+            });
+
+            child_part_fns.push(parse_quote! {
+                /// Removes the part with the given relationship id together with its relationship
+                /// and content type registration.
+                pub fn #delete_fn_ident(
+                    &mut self,
+                    #content_types_param
+                    r_id: &str,
+                ) -> Option<#child_type> {
+                    let index = self
+                        .#child_api_name_ident
+                        .iter()
+                        .position(|child| child.r_id == r_id)?;
+
+                    let #child_name_ident = self.#child_api_name_ident.remove(index);
+
+                    if let Some(relationships) = &mut self.relationships {
+                        relationships.remove_relationship(r_id);
+                    }
+
+                    #content_types_expr.remove_override(&#child_name_ident.inner_path);
+
+                    Some(#child_name_ident)
+                }
+            });
+        } else {
+            let content_types_arg: Option<TokenStream> = content_types_param
+                .as_ref()
+                .map(|_| quote! { content_types });
+
+            child_part_fns.push(parse_quote! {
+                /// Adds the part, replacing and deleting the current one if present.
+                pub fn #add_fn_ident(
+                    &mut self,
+                    #content_types_param
+                    #content_type_param
+                    mut #child_name_ident: #child_type,
+                ) -> &mut #child_type {
+                    self.#delete_fn_ident(#content_types_arg);
+
+                    #( #wire_stmt_list )*
+
+                    self.#child_api_name_ident
+                        .insert(std::boxed::Box::new(#child_name_ident))
+                }
+            });
+
+            child_part_fns.push(parse_quote! {
+                /// Removes the part together with its relationship and content type
+                /// registration.
+                pub fn #delete_fn_ident(
+                    &mut self,
+                    #content_types_param
+                ) -> Option<std::boxed::Box<#child_type>> {
+                    let #child_name_ident = self.#child_api_name_ident.take()?;
+
+                    if let Some(relationships) = &mut self.relationships {
+                        relationships.remove_relationship(&#child_name_ident.r_id);
+                    }
+
+                    #content_types_expr.remove_override(&#child_name_ident.inner_path);
+
+                    Some(#child_name_ident)
+                }
+            });
+        }
+    }
+
+    return Ok(child_part_fns);
+}
+
+fn gen_external_relationship_fns() -> TokenStream {
+    return quote! {
+        pub fn external_relationships(
+            &self,
+        ) -> impl Iterator<Item = &crate::common::opc_relationships::Relationship> {
+            self.relationships
+                .iter()
+                .flat_map(|relationships| relationships.external_relationships())
+        }
+
+        pub fn get_external_relationship(
+            &self,
+            r_id: &str,
+        ) -> Option<&crate::common::opc_relationships::Relationship> {
+            self.external_relationships().find(|relationship| relationship.id == r_id)
+        }
+
+        pub fn add_external_relationship(&mut self, relationship_type: &str, target: &str) -> String {
+            if self.rels_path.is_empty() {
+                self.rels_path = resolve_rels_path(&self.inner_path);
+            }
+
+            self.relationships
+                .get_or_insert_with(crate::common::opc_relationships::Relationships::new)
+                .add_external_relationship(relationship_type, target)
+        }
+
+        pub fn remove_external_relationship(
+            &mut self,
+            r_id: &str,
+        ) -> Option<crate::common::opc_relationships::Relationship> {
+            let relationships = self.relationships.as_mut()?;
+
+            if !relationships.get_relationship(r_id)?.is_external() {
+                return None;
+            }
+
+            relationships.remove_relationship(r_id)
+        }
+    };
+}
+
+pub fn gen_data_part_reference_relationship(name: &str) -> Result<String, BuildErrorReport> {
+    let use_common_glob = gen_use_common_glob();
+
+    let relationship_type_str = match name {
+        "AudioReferenceRelationship" => {
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/audio"
+        }
+        "VideoReferenceRelationship" => {
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/video"
+        }
+        "MediaReferenceRelationship" => {
+            "http://schemas.microsoft.com/office/2007/relationships/media"
+        }
+        _ => {
+            return Err(BuildError::UnknownDataPartReferenceError(name.to_string()).into());
+        }
+    };
+
+    let struct_name_ident: Ident =
+        parse_str(&name.to_upper_camel_case()).context_transform(BuildError::from)?;
+
+    Ok(quote! {
+        #use_common_glob
+
+        #[derive(Clone, Debug, Default)]
+        pub struct #struct_name_ident {
+            pub r_id: String,
+            pub data_part: crate::parts::media_data_part::MediaDataPart,
+        }
+
+        impl #struct_name_ident {
+            pub const RELATIONSHIP: &str = #relationship_type_str;
+
+            pub(crate) fn new_from_package_reader(
+                path: &str,
+                r_id: &str,
+                package_reader: &mut crate::parts::package_reader::PackageReader<'_>,
+            ) -> Result<Self, SdkErrorReport> {
+                Ok(Self {
+                    r_id: r_id.to_string(),
+                    data_part: crate::parts::media_data_part::MediaDataPart::new_from_package_reader(
+                        path,
+                        package_reader,
+                    )?,
+                })
+            }
+
+            pub(crate) fn save_zip<W: std::io::Write + std::io::Seek>(
+                &self,
+                zip: &mut zip::ZipWriter<W>,
+                entry_set: &mut std::collections::HashSet<String>,
+                save_options: &crate::common::SaveOptions,
+            ) -> Result<(), SdkErrorReport> {
+                self.data_part.save_zip(zip, entry_set, save_options)
+            }
+        }
+    }
+    .to_string())
+}
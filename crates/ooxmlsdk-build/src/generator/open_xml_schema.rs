@@ -2,14 +2,17 @@ use heck::ToUpperCamelCase;
 use proc_macro2::TokenStream;
 use quote::quote;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use syn::{Ident, ItemEnum, Type, Variant, parse_str, parse2};
+use syn::{Ident, ItemEnum, Type, Variant, parse_quote, parse_str, parse2};
 
 use crate::{
     error::*,
-    generator::{context::GenContext, simple_type::simple_type_mapping},
+    generator::{
+        context::GenContext,
+        simple_type::{is_string_simple_type, simple_type_mapping},
+    },
     models::{
         Occurrence, OpenXmlNamespace, OpenXmlSchema, OpenXmlSchemaEnum, OpenXmlSchemaType,
-        OpenXmlSchemaTypeAttribute, OpenXmlSchemaTypeChild,
+        OpenXmlSchemaTypeAttribute,
     },
     utils::HashMapOpsError,
 };
@@ -24,7 +27,7 @@ pub fn gen_open_xml_schemas(
         &schema
             .types
             .par_iter()
-            .map(|schema_type| gen_schema_type(schema, schema_type, gen_context))
+            .map(|schema_type| gen_schema_type(schema, schema_type, gen_context, false))
             .collect::<Result<Vec<_>, _>>()?
             .join("\n"),
     );
@@ -41,10 +44,26 @@ pub fn gen_open_xml_schemas(
     Ok(contents)
 }
 
+/// Generates the borrowed representation of the types holding strings, see
+/// [`GenContext::is_borrowed_type`]. Enums and the other types are shared with `crate::schemas`.
+pub fn gen_borrowed_open_xml_schemas(
+    schema: &OpenXmlSchema,
+    gen_context: &GenContext,
+) -> Result<String, BuildErrorReport> {
+    return Ok(schema
+        .types
+        .par_iter()
+        .filter(|schema_type| gen_context.is_borrowed_type(schema_type))
+        .map(|schema_type| gen_schema_type(schema, schema_type, gen_context, true))
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n"));
+}
+
 fn gen_schema_type(
     schema: &OpenXmlSchema,
     schema_type: &OpenXmlSchemaType,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<String, BuildErrorReport> {
     let schema_namespace = gen_context
         .uri_namespace_map
@@ -58,17 +77,18 @@ fn gen_schema_type(
 
     if schema_type.base_class == "OpenXmlLeafTextElement" {
         for attr in &schema_type.attributes {
-            fields.push(gen_attr(attr, schema_namespace, gen_context)?);
+            fields.push(gen_attr(attr, schema_namespace, gen_context, borrowed)?);
         }
 
-        let simple_type_name = gen_xml_content_type(schema_type, schema_namespace, gen_context)?;
+        let simple_type_name =
+            gen_xml_content_type(schema_type, schema_namespace, gen_context, borrowed)?;
 
         fields.push(quote! {
             pub xml_content: Option<#simple_type_name>,
         });
     } else if schema_type.base_class == "OpenXmlLeafElement" {
         for attr in &schema_type.attributes {
-            fields.push(gen_attr(attr, schema_namespace, gen_context)?);
+            fields.push(gen_attr(attr, schema_namespace, gen_context, borrowed)?);
         }
     } else if schema_type.base_class == "OpenXmlCompositeElement"
         || schema_type.base_class == "CustomXmlElement"
@@ -80,35 +100,46 @@ fn gen_schema_type(
             || schema.target_namespace == "http://schemas.openxmlformats.org/drawingml/2006/main"
             || schema.target_namespace == "http://schemas.openxmlformats.org/drawingml/2006/picture"
         {
-            fields.push(quote! {
-                pub xmlns: Option<String>,
-            });
-
-            fields.push(quote! {
-                pub xmlns_map: std::collections::HashMap<String, String>,
-            });
-
-            fields.push(quote! {
-                pub mc_ignorable: Option<String>,
-            });
+            if borrowed {
+                fields.push(quote! {
+                    pub xmlns: Option<std::borrow::Cow<'de, str>>,
+                });
+
+                fields.push(quote! {
+                    #[doc = " The `xmlns:*` declarations in document order."]
+                    pub xmlns_map: Vec<(&'de str, std::borrow::Cow<'de, str>)>,
+                });
+
+                fields.push(quote! {
+                    pub mc_ignorable: Option<std::borrow::Cow<'de, str>>,
+                });
+            } else {
+                fields.push(quote! {
+                    pub xmlns: Option<String>,
+                });
+
+                fields.push(quote! {
+                    pub xmlns_map: std::collections::BTreeMap<String, String>,
+                });
+
+                fields.push(quote! {
+                    pub mc_ignorable: Option<String>,
+                });
+            }
         }
 
         for attr in &schema_type.attributes {
-            fields.push(gen_attr(attr, schema_namespace, gen_context)?);
+            fields.push(gen_attr(attr, schema_namespace, gen_context, borrowed)?);
         }
 
         if schema_type.is_one_sequence_flatten() {
             let one_sequence_fields =
-                gen_one_sequence_fields(schema_type, schema_namespace, gen_context)?;
+                gen_one_sequence_fields(schema_type, schema_namespace, gen_context, borrowed)?;
 
             fields.extend(one_sequence_fields);
         } else {
-            let (field_option, enum_option) = gen_children(
-                &schema_type.class_name,
-                &schema_type.children,
-                schema_namespace,
-                gen_context,
-            )?;
+            let (field_option, enum_option) =
+                gen_children(schema_type, schema_namespace, gen_context, borrowed)?;
 
             if let Some(field) = field_option {
                 fields.push(field);
@@ -116,32 +147,30 @@ fn gen_schema_type(
 
             child_choice_enum_option = enum_option;
         }
+
+        fields.push(gen_raw_content_fields(borrowed));
     } else if schema_type.is_derived {
         let base_class_type = gen_context
             .type_name_type_map
             .try_get(format!("{type_base_class}/").as_str())?;
 
         for attr in &schema_type.attributes {
-            fields.push(gen_attr(attr, schema_namespace, gen_context)?);
+            fields.push(gen_attr(attr, schema_namespace, gen_context, borrowed)?);
         }
 
         for attr in &base_class_type.attributes {
-            fields.push(gen_attr(attr, schema_namespace, gen_context)?);
+            fields.push(gen_attr(attr, schema_namespace, gen_context, borrowed)?);
         }
 
         if schema_type.is_one_sequence_flatten() && base_class_type.composite_type == "OneSequence"
         {
             let one_sequence_fields =
-                gen_one_sequence_fields(schema_type, schema_namespace, gen_context)?;
+                gen_one_sequence_fields(schema_type, schema_namespace, gen_context, borrowed)?;
 
             fields.extend(one_sequence_fields);
         } else {
-            let (field_option, enum_option) = gen_children(
-                &schema_type.class_name,
-                &schema_type.children,
-                schema_namespace,
-                gen_context,
-            )?;
+            let (field_option, enum_option) =
+                gen_children(schema_type, schema_namespace, gen_context, borrowed)?;
 
             if let Some(field) = field_option {
                 fields.push(field);
@@ -150,10 +179,14 @@ fn gen_schema_type(
             child_choice_enum_option = enum_option;
         }
 
+        if base_class_type.is_composite() {
+            fields.push(gen_raw_content_fields(borrowed));
+        }
+
         if schema_type.children.is_empty() && base_class_type.base_class == "OpenXmlLeafTextElement"
         {
             let simple_type_name =
-                gen_xml_content_type(base_class_type, schema_namespace, gen_context)?;
+                gen_xml_content_type(base_class_type, schema_namespace, gen_context, borrowed)?;
 
             fields.push(quote! {
                 pub xml_content: Option<#simple_type_name>,
@@ -166,6 +199,8 @@ fn gen_schema_type(
     let struct_name_ident: Ident =
         parse_str(&schema_type.class_name.to_upper_camel_case()).unwrap();
 
+    let struct_generics = borrowed.then(|| quote! { <'de> });
+
     let summary_doc = format!(" {}", schema_type.summary);
 
     let version_doc = if schema_type.version.is_empty() {
@@ -189,7 +224,7 @@ fn gen_schema_type(
         #[doc = ""]
         #[doc = #qualified_doc]
         #[derive(Clone, Debug, Default)]
-        pub struct #struct_name_ident {
+        pub struct #struct_name_ident #struct_generics {
             #( #fields )*
         }
 
@@ -238,10 +273,13 @@ fn gen_attr(
     schema: &OpenXmlSchemaTypeAttribute,
     schema_namespace: &OpenXmlNamespace,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<TokenStream, BuildErrorReport> {
     let attr_name_ident = schema.as_name_ident();
 
-    let type_ident_raw = if schema.r#type.starts_with("ListValue<") {
+    let type_ident_raw = if borrowed && is_string_simple_type(&schema.r#type) {
+        "std::borrow::Cow<'de, str>".to_string()
+    } else if schema.r#type.starts_with("ListValue<") {
         "String".to_string()
     } else if schema.r#type.starts_with("EnumValue<") {
         let (enum_typed_namespace_str, enum_name) = schema.split_type_enum_value_trimmed();
@@ -267,7 +305,7 @@ fn gen_attr(
 
         let enum_namespace = gen_context.prefix_namespace_map.try_get(enum_prefix)?;
 
-        if enum_namespace.prefix == schema_namespace.prefix {
+        if enum_namespace.prefix == schema_namespace.prefix && !borrowed {
             enum_name_formatted
         } else {
             let enum_schema = gen_context
@@ -319,20 +357,51 @@ fn gen_attr(
 }
 
 fn gen_children(
-    class_name: &str,
-    children: &Vec<OpenXmlSchemaTypeChild>,
+    schema_type: &OpenXmlSchemaType,
     schema_namespace: &OpenXmlNamespace,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<(Option<TokenStream>, Option<ItemEnum>), BuildErrorReport> {
+    let children = &schema_type.children;
+
     if children.is_empty() {
         return Ok((None, None));
     }
 
-    let child_choice_enum_ident: Ident =
-        parse_str(&format!("{}ChildChoice", class_name.to_upper_camel_case())).unwrap();
+    let child_choice_enum_name = format!(
+        "{}ChildChoice",
+        schema_type.class_name.to_upper_camel_case()
+    );
+
+    // The borrowed representation shares the child choice enum if no child is borrowed.
+    if borrowed
+        && !children.iter().any(|child| {
+            gen_context
+                .type_name_type_map
+                .get(child.name.as_str())
+                .is_some_and(|child_type| gen_context.is_borrowed_type(child_type))
+        })
+    {
+        let child_choice_enum_type: Type = parse_str(&format!(
+            "crate::schemas::{}::{child_choice_enum_name}",
+            schema_type.module_name
+        ))
+        .unwrap();
+
+        return Ok((
+            Some(quote! {
+                pub children: Vec<#child_choice_enum_type>,
+            }),
+            None,
+        ));
+    }
+
+    let child_choice_enum_ident: Ident = parse_str(&child_choice_enum_name).unwrap();
+
+    let child_choice_enum_generics = borrowed.then(|| quote! { <'de> });
 
     let field_option = Some(quote! {
-        pub children: Vec<#child_choice_enum_ident>,
+        pub children: Vec<#child_choice_enum_ident #child_choice_enum_generics>,
     });
 
     let mut variants: Vec<TokenStream> = vec![];
@@ -346,15 +415,17 @@ fn gen_children(
             .try_get(child.name.as_str())?;
         let child_schema_name = child_type.class_name.to_upper_camel_case();
 
-        let child_variant_type_raw = if child_namespace.prefix == schema_namespace.prefix {
-            child_schema_name
+        let child_variant_type: Type = if borrowed {
+            gen_context.schema_type_path(child_type, true)
+        } else if child_namespace.prefix == schema_namespace.prefix {
+            parse_str(&child_schema_name).unwrap()
         } else {
-            format!(
+            parse_str(&format!(
                 "crate::schemas::{}::{child_schema_name}",
                 &child_type.module_name
-            )
+            ))
+            .unwrap()
         };
-        let child_variant_type: Type = parse_str(&child_variant_type_raw).unwrap();
 
         let child_variant_name_ident = child.as_last_name_ident();
 
@@ -366,7 +437,7 @@ fn gen_children(
     let enum_option = Some(
         parse2(quote! {
             #[derive(Clone, Debug)]
-            pub enum #child_choice_enum_ident {
+            pub enum #child_choice_enum_ident #child_choice_enum_generics {
                 #( #variants )*
             }
         })
@@ -376,14 +447,39 @@ fn gen_children(
     Ok((field_option, enum_option))
 }
 
+fn gen_raw_content_fields(borrowed: bool) -> TokenStream {
+    if borrowed {
+        return quote! {
+            #[doc = " Attributes not known to the schema, kept verbatim."]
+            pub raw_attributes: Vec<crate::common::BorrowedRawAttribute<'de>>,
+            #[doc = " Child markup kept verbatim, i.e. elements not known to the schema and preserved"]
+            #[doc = " `mc:AlternateContent` blocks."]
+            pub raw_children: Vec<crate::common::BorrowedRawChild<'de>>,
+        };
+    }
+
+    quote! {
+        #[doc = " Attributes not known to the schema, kept verbatim."]
+        pub raw_attributes: Vec<crate::common::RawAttribute>,
+        #[doc = " Child markup kept verbatim, i.e. elements not known to the schema and preserved"]
+        #[doc = " `mc:AlternateContent` blocks."]
+        pub raw_children: Vec<crate::common::RawChild>,
+    }
+}
+
 fn gen_xml_content_type(
     schema_type: &OpenXmlSchemaType,
     schema_namespace: &OpenXmlNamespace,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<Type, BuildErrorReport> {
     let (first_name, _) = schema_type.split_name();
 
     let Some(schema_enum) = gen_context.enum_type_enum_map.get(first_name) else {
+        if borrowed && is_string_simple_type(simple_type_mapping(first_name)) {
+            return Ok(parse_quote!(std::borrow::Cow<'de, str>));
+        }
+
         return Ok(parse_str(&format!(
             "crate::common::simple_type::{}",
             simple_type_mapping(first_name)
@@ -394,7 +490,7 @@ fn gen_xml_content_type(
     let enum_namespace = gen_context
         .enum_type_namespace_map
         .try_get(schema_enum.r#type.as_str())?;
-    if enum_namespace.prefix == schema_namespace.prefix {
+    if enum_namespace.prefix == schema_namespace.prefix && !borrowed {
         return Ok(parse_str(&schema_enum.name.to_upper_camel_case()).map_err(BuildError::from)?);
     }
 
@@ -410,6 +506,7 @@ fn gen_one_sequence_fields(
     schema_type: &OpenXmlSchemaType,
     schema_namespace: &OpenXmlNamespace,
     gen_context: &GenContext,
+    borrowed: bool,
 ) -> Result<Vec<TokenStream>, BuildErrorReport> {
     let mut fields: Vec<TokenStream> = vec![];
 
@@ -425,15 +522,17 @@ fn gen_one_sequence_fields(
             .try_get(child.name.as_str())?;
         let child_schema_name = child_type.class_name.to_upper_camel_case();
 
-        let child_variant_type_raw = if child_namespace.prefix == schema_namespace.prefix {
-            child_schema_name
+        let child_variant_type: Type = if borrowed {
+            gen_context.schema_type_path(child_type, true)
+        } else if child_namespace.prefix == schema_namespace.prefix {
+            parse_str(&child_schema_name).unwrap()
         } else {
-            format!(
+            parse_str(&format!(
                 "crate::schemas::{}::{child_schema_name}",
                 &child_type.module_name
-            )
+            ))
+            .unwrap()
         };
-        let child_variant_type: Type = parse_str(&child_variant_type_raw).unwrap();
 
         let child_property_name_ident = child.as_property_name_ident();
 
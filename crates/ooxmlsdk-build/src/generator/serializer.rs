@@ -66,13 +66,13 @@ fn gen_schema_type(
     let (_, type_prefixed_name) = schema_type.split_name();
     let (_, type_name) = schema_type.split_last_name();
 
-    let attributes_ident = parse_quote!(attributes);
+    let attributes_ident = parse_quote!(writer);
     let mut xml_tag_attributes_inner: Vec<TokenStream> = vec![];
     for attribute in &schema_type.attributes {
         xml_tag_attributes_inner.push(gen_attr(attribute, &attributes_ident));
     }
 
-    let xml_inner_ident = parse_quote!(xml);
+    let xml_inner_ident = parse_quote!(writer);
     let xml_inner_writer = gen_inner_writer(
         schema,
         schema_type,
@@ -82,35 +82,75 @@ fn gen_schema_type(
         gen_context,
     )?;
 
+    let has_raw_content = match schema_type.base_class.as_str() {
+        "OpenXmlLeafTextElement" | "OpenXmlLeafElement" => false,
+        _ if schema_type.is_composite() => true,
+        _ if schema_type.is_derived => gen_context
+            .type_name_type_map
+            .try_get(format!("{}/", schema_type.split_name().0).as_str())?
+            .is_composite(),
+        _ => false,
+    };
+
+    if has_raw_content {
+        xml_tag_attributes_inner.push(quote! {
+          for raw_attribute in &self.raw_attributes {
+            #attributes_ident.write_escaped_attribute(&raw_attribute.name, &raw_attribute.value)?;
+          }
+        });
+    }
+
     // TODO: Is this needed?
     // let xml_needs_header =
     //     !schema_type.part.is_empty() || schema_type.base_class == "OpenXmlPartRootElement";
 
-    let xml_tag_attributes_xmlns_inner = if !schema_type.part.is_empty()
-        || schema_type.base_class == "OpenXmlPartRootElement"
+    let is_part_root =
+        !schema_type.part.is_empty() || schema_type.base_class == "OpenXmlPartRootElement";
+
+    let has_xmlns = is_part_root
         || ((schema_type.base_class == "OpenXmlCompositeElement"
             || schema_type.base_class == "CustomXmlElement"
             || schema_type.base_class == "OpenXmlPartRootElement"
             || schema_type.base_class == "SdtElement")
-            && (schema.target_namespace == "http://schemas.openxmlformats.org/drawingml/2006/main"
+            && (schema.target_namespace
+                == "http://schemas.openxmlformats.org/drawingml/2006/main"
                 || schema.target_namespace
-                    == "http://schemas.openxmlformats.org/drawingml/2006/picture"))
-    {
+                    == "http://schemas.openxmlformats.org/drawingml/2006/picture"));
+
+    // Part roots declare the prefixes used within the part, so they are written without a
+    // default namespace.
+    let xmlns_missing_stmt: Option<TokenStream> = (!is_part_root).then(|| {
+        quote! {
+          else {
+            tracing::warn!("with_xmlns is true, but {}::xmlns is None.", Self::NAME);
+          }
+        }
+    });
+
+    let used_xmlns_stmt: Option<TokenStream> = is_part_root.then(|| {
+        quote! {
+          if with_xmlns {
+            write_used_xmlns_attributes(#attributes_ident, self, |prefix| self.xmlns_map.contains_key(prefix))?;
+          }
+        }
+    });
+
+    let xml_tag_attributes_xmlns_inner = if has_xmlns {
         Some(quote! {
-          if with_xmlns{
+          if with_xmlns {
             if let Some(xmlns) = &self.xmlns {
-                #attributes_ident.push_str(&as_xml_attribute("xmlns", xmlns));
-            } else {
-                tracing::warn!("with_xmlns is true, but {}::xmlns is None.", Self::NAME);
-            }
+                #attributes_ident.write_attribute("xmlns", xmlns)?;
+            } #xmlns_missing_stmt
           }
 
           for (key, value) in &self.xmlns_map {
-            #attributes_ident.push_str(&as_xml_attribute(&format!("xmlns:{key}"), value));
+            #attributes_ident.write_xmlns_attribute(key, value)?;
           }
 
+          #used_xmlns_stmt
+
           if let Some(mc_ignorable) = &self.mc_ignorable {
-            #attributes_ident.push_str(&as_xml_attribute("mc:Ignorable", mc_ignorable));
+            #attributes_ident.write_attribute("mc:Ignorable", mc_ignorable)?;
           }
         })
     } else {
@@ -121,22 +161,26 @@ fn gen_schema_type(
         if xml_tag_attributes_xmlns_inner.is_some() || !xml_tag_attributes_inner.is_empty() {
             parse_quote! {
               #[allow(unused_variables)]
-              fn xml_tag_attributes(&self, with_xmlns: bool) -> Option<String> {
-                  let mut #attributes_ident = String::with_capacity(
-                    const { "xmlns".len() + "xmlns:".len() + "mc:Ignorable".len() + 64 },
-                  );
-
+              fn write_xml_tag_attributes<W: std::io::Write>(
+                  &self,
+                  #attributes_ident: &mut XmlWriter<W>,
+                  with_xmlns: bool,
+              ) -> std::io::Result<()> {
                   #xml_tag_attributes_xmlns_inner
 
                   #( #xml_tag_attributes_inner )*
 
-                  return Some(#attributes_ident);
+                  Ok(())
               }
             }
         } else {
             parse_quote! {
-              fn xml_tag_attributes(&self, _with_xmlns: bool) -> Option<String> {
-                  return None;
+              fn write_xml_tag_attributes<W: std::io::Write>(
+                  &self,
+                  _writer: &mut XmlWriter<W>,
+                  _with_xmlns: bool,
+              ) -> std::io::Result<()> {
+                  Ok(())
               }
             }
         };
@@ -144,22 +188,31 @@ fn gen_schema_type(
     let xml_inner: ImplItemFn = if xml_inner_writer.is_some() {
         parse_quote!(
             #[allow(unused_variables)]
-            fn xml_inner(&self, with_xmlns: bool) -> Option<String> {
-                let mut #xml_inner_ident = String::with_capacity(512);
-
+            fn write_xml_inner<W: std::io::Write>(
+                &self,
+                #xml_inner_ident: &mut XmlWriter<W>,
+                with_xmlns: bool,
+            ) -> std::io::Result<()> {
                 #xml_inner_writer
 
-                return Some(#xml_inner_ident);
+                Ok(())
             }
         )
     } else {
         parse_quote! {
-            fn xml_inner(&self, _with_xmlns: bool) -> Option<String> {
-                return None;
+            fn write_xml_inner<W: std::io::Write>(
+                &self,
+                _writer: &mut XmlWriter<W>,
+                _with_xmlns: bool,
+            ) -> std::io::Result<()> {
+                Ok(())
             }
         }
     };
 
+    let collect_xml_prefixes =
+        gen_collect_xml_prefixes_fn(schema, schema_type, has_raw_content, has_xmlns, gen_context)?;
+
     return Ok(quote!(
       impl Serializeable for #struct_type {
           const PREFIXED_NAME: &str = #type_prefixed_name;
@@ -169,6 +222,8 @@ fn gen_schema_type(
           #xml_tag_attributes
 
           #xml_inner
+
+          #collect_xml_prefixes
       }
     )
     .to_string());
@@ -212,14 +267,25 @@ fn gen_attr(schema: &OpenXmlSchemaTypeAttribute, attributes_ident: &Ident) -> To
     let attr_value_ident = schema.as_name_ident();
     let attr_name_str = schema.as_name_str();
 
+    // Only declared by leaf text elements, whose whitespace is kept by readers only if preserved.
+    if schema.q_name == "xml:space" {
+        return quote! {
+          if let Some(#attr_value_ident) = &self.#attr_value_ident {
+            #attributes_ident.write_escaped_attribute(#attr_name_str, #attr_value_ident)?;
+          } else if self.xml_content.as_deref().is_some_and(needs_space_preserve) {
+            #attributes_ident.write_attribute(#attr_name_str, "preserve")?;
+          }
+        };
+    }
+
     if schema.is_validator_required() {
         quote! {
-          #attributes_ident.push_str(&as_xml_attribute(#attr_name_str, &quick_xml::escape::escape(self.#attr_value_ident.to_string())));
+          #attributes_ident.write_escaped_attribute(#attr_name_str, &self.#attr_value_ident)?;
         }
     } else {
         quote! {
           if let Some(#attr_value_ident) = &self.#attr_value_ident {
-            #attributes_ident.push_str(&as_xml_attribute(#attr_name_str, &quick_xml::escape::escape(#attr_value_ident.to_string())));
+            #attributes_ident.write_escaped_attribute(#attr_name_str, #attr_value_ident)?;
           }
         }
     }
@@ -235,7 +301,7 @@ fn gen_children_match<'a>(
               let child_name_ident = child.as_last_name_ident();
 
             parse_quote! {
-              #child_choice_enum_type::#child_name_ident(child) => #xml_inner_ident.push_str(&child.to_xml_string(false, with_xmlns)),
+              #child_choice_enum_type::#child_name_ident(child) => child.write_xml_element(#xml_inner_ident, with_xmlns)?,
             }
         }).collect::<Vec<_>>();
 
@@ -244,11 +310,15 @@ fn gen_children_match<'a>(
     }
 
     return Some(quote! {
-        for child in &self.children {
+        for (child_position, child) in self.children.iter().enumerate() {
+            #xml_inner_ident.write_raw_children(&self.raw_children, child_position..=child_position)?;
+
             match child {
                 #( #child_arms )*
             };
         }
+
+        #xml_inner_ident.write_raw_children(&self.raw_children, self.children.len()..)?;
     });
 }
 
@@ -267,21 +337,29 @@ fn gen_sequence_flatten_match(
             Occurrence::Required => {
                 child_stmt_list.push(
                     parse_quote! {
-                      #xml_inner_ident.push_str(&self.#child_name_ident.to_xml_string(false, with_xmlns));
+                      {
+                        #xml_inner_ident.write_raw_children(&self.raw_children, child_position..=child_position)?;
+                        self.#child_name_ident.write_xml_element(#xml_inner_ident, with_xmlns)?;
+                        child_position += 1;
+                      }
                     },
                 );
             }
             Occurrence::Optional => {
                 child_stmt_list.push(parse_quote! {
                   if let Some(#child_name_ident) = &self.#child_name_ident {
-                    #xml_inner_ident.push_str(&#child_name_ident.to_xml_string(false, with_xmlns));
+                    #xml_inner_ident.write_raw_children(&self.raw_children, child_position..=child_position)?;
+                    #child_name_ident.write_xml_element(#xml_inner_ident, with_xmlns)?;
+                    child_position += 1;
                   }
                 });
             }
             Occurrence::Repeated => {
                 child_stmt_list.push(parse_quote! {
                   for child in &self.#child_name_ident {
-                    #xml_inner_ident.push_str(&child.to_xml_string(false, with_xmlns));
+                    #xml_inner_ident.write_raw_children(&self.raw_children, child_position..=child_position)?;
+                    child.write_xml_element(#xml_inner_ident, with_xmlns)?;
+                    child_position += 1;
                   }
                 });
             }
@@ -289,7 +367,11 @@ fn gen_sequence_flatten_match(
     }
 
     return Ok(quote! {
+      let mut child_position = 0;
+
       #( #child_stmt_list )*
+
+      #xml_inner_ident.write_raw_children(&self.raw_children, child_position..)?;
     });
 }
 
@@ -315,7 +397,7 @@ fn gen_inner_writer(
         "OpenXmlLeafTextElement" => {
             return Ok(Some(quote! {
               if let Some(xml_content) = &self.xml_content {
-                #xml_inner_ident.push_str(&quick_xml::escape::escape(xml_content.to_string()));
+                #xml_inner_ident.write_text(xml_content)?;
               }
             }));
         }
@@ -324,7 +406,9 @@ fn gen_inner_writer(
         | "OpenXmlPartRootElement"
         | "SdtElement" => {
             if schema_type.children.is_empty() {
-                return Ok(None);
+                return Ok(Some(quote! {
+                  #xml_inner_ident.write_raw_children(&self.raw_children, ..)?;
+                }));
             }
 
             if schema_type.is_one_sequence_flatten() {
@@ -357,11 +441,17 @@ fn gen_inner_writer(
                 if base_class_type.base_class == "OpenXmlLeafTextElement" {
                     return Ok(Some(quote! {
                       if let Some(xml_content) = &self.xml_content {
-                        #xml_inner_ident.push_str(&quick_xml::escape::escape(xml_content.to_string()));
+                        #xml_inner_ident.write_text(xml_content)?;
                       }
                     }));
                 };
 
+                if base_class_type.is_composite() {
+                    return Ok(Some(quote! {
+                      #xml_inner_ident.write_raw_children(&self.raw_children, ..)?;
+                    }));
+                }
+
                 return Ok(None);
             }
 
@@ -387,3 +477,174 @@ fn gen_inner_writer(
         ),
     }
 }
+
+/// Generates `collect_xml_prefixes`, following the same children as [`gen_inner_writer`].
+fn gen_collect_xml_prefixes_fn(
+    schema: &OpenXmlSchema,
+    schema_type: &OpenXmlSchemaType,
+    has_raw_content: bool,
+    has_xmlns: bool,
+    gen_context: &GenContext,
+) -> Result<ImplItemFn, BuildErrorReport> {
+    let (type_base_class, _) = schema_type.split_name();
+
+    let mut attributes: Vec<&OpenXmlSchemaTypeAttribute> = schema_type.attributes.iter().collect();
+
+    let mut stmt_list: Vec<TokenStream> = vec![];
+
+    let child_choice_enum_type: Type = parse_str(&format!(
+        "crate::schemas::{}::{}ChildChoice",
+        &schema.module_name,
+        schema_type.class_name.to_upper_camel_case()
+    ))
+    .map_err(BuildError::from)?;
+
+    match schema_type.base_class.as_str() {
+        "OpenXmlLeafElement" | "OpenXmlLeafTextElement" => {}
+        "OpenXmlCompositeElement"
+        | "CustomXmlElement"
+        | "OpenXmlPartRootElement"
+        | "SdtElement" => {
+            if schema_type.is_one_sequence_flatten() && !schema_type.children.is_empty() {
+                stmt_list.push(gen_sequence_flatten_prefixes(schema_type)?);
+            } else {
+                stmt_list.extend(gen_children_prefixes(
+                    schema_type.children.iter(),
+                    &child_choice_enum_type,
+                ));
+            }
+        }
+        _ if schema_type.is_derived => {
+            let base_class_type = gen_context
+                .type_name_type_map
+                .try_get(format!("{type_base_class}/").as_str())?;
+
+            attributes.extend(&base_class_type.attributes);
+
+            // Children must be deduped
+            let children: HashMap<_, _> =
+                HashMap::from_iter(base_class_type.child_iter().chain(schema_type.child_iter()));
+
+            if schema_type.is_one_sequence_flatten()
+                && base_class_type.composite_type == "OneSequence"
+                && !children.is_empty()
+            {
+                stmt_list.push(gen_sequence_flatten_prefixes(schema_type)?);
+            } else {
+                stmt_list.extend(gen_children_prefixes(
+                    children.into_values(),
+                    &child_choice_enum_type,
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    for attribute in attributes {
+        let Some((attr_prefix, _)) = attribute.as_name_str().split_once(':') else {
+            continue;
+        };
+
+        if attr_prefix == "xml" {
+            continue;
+        }
+
+        let attr_value_ident = attribute.as_name_ident();
+
+        stmt_list.push(if attribute.is_validator_required() {
+            quote! {
+              prefixes.insert(#attr_prefix);
+            }
+        } else {
+            quote! {
+              if self.#attr_value_ident.is_some() {
+                prefixes.insert(#attr_prefix);
+              }
+            }
+        });
+    }
+
+    if has_raw_content {
+        stmt_list.push(quote! {
+          for raw_attribute in &self.raw_attributes {
+            insert_xml_prefix(prefixes, &raw_attribute.name);
+          }
+        });
+    }
+
+    if has_xmlns {
+        stmt_list.push(quote! {
+          if let Some(mc_ignorable) = &self.mc_ignorable {
+            prefixes.insert("mc");
+            prefixes.extend(mc_ignorable.split_whitespace());
+          }
+        });
+    }
+
+    return Ok(parse_quote! {
+      fn collect_xml_prefixes<'a>(&'a self, prefixes: &mut std::collections::BTreeSet<&'a str>) {
+          insert_xml_prefix(prefixes, Self::PREFIXED_NAME);
+
+          #( #stmt_list )*
+      }
+    });
+}
+
+fn gen_children_prefixes<'a>(
+    children: impl Iterator<Item = &'a OpenXmlSchemaTypeChild>,
+    child_choice_enum_type: &Type,
+) -> Option<TokenStream> {
+    let child_arms = children
+        .map(|child| -> TokenStream {
+            let child_name_ident = child.as_last_name_ident();
+
+            parse_quote! {
+              #child_choice_enum_type::#child_name_ident(child) => child.collect_xml_prefixes(prefixes),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if child_arms.is_empty() {
+        return None;
+    }
+
+    return Some(quote! {
+        for child in &self.children {
+            match child {
+                #( #child_arms )*
+            };
+        }
+    });
+}
+
+fn gen_sequence_flatten_prefixes(
+    schema_type: &OpenXmlSchemaType,
+) -> Result<TokenStream, BuildErrorReport> {
+    let child_map = schema_type.child_map();
+    let mut child_stmt_list: Vec<Stmt> = vec![];
+
+    for schema_type_particle in &schema_type.particle.items {
+        let child = child_map.try_get(schema_type_particle.name.as_str())?;
+        let child_name_ident = child.as_property_name_ident();
+
+        child_stmt_list.push(match schema_type_particle.as_occurrence() {
+            Occurrence::Required => parse_quote! {
+              self.#child_name_ident.collect_xml_prefixes(prefixes);
+            },
+            Occurrence::Optional => parse_quote! {
+              if let Some(#child_name_ident) = &self.#child_name_ident {
+                #child_name_ident.collect_xml_prefixes(prefixes);
+              }
+            },
+            Occurrence::Repeated => parse_quote! {
+              for child in &self.#child_name_ident {
+                child.collect_xml_prefixes(prefixes);
+              }
+            },
+        });
+    }
+
+    return Ok(quote! {
+      #( #child_stmt_list )*
+    });
+}
@@ -94,3 +94,18 @@ pub fn simple_type_mapping(name: &str) -> &str {
         _ => name,
     }
 }
+
+/// Whether an attribute or text content type is represented by a string.
+pub fn is_string_simple_type(name: &str) -> bool {
+    return name.starts_with("ListValue<")
+        || matches!(
+            name,
+            "Base64BinaryValue"
+                | "DateTimeValue"
+                | "DecimalValue"
+                | "HexBinaryValue"
+                | "IntegerValue"
+                | "SByteValue"
+                | "StringValue"
+        );
+}
@@ -1,9 +1,10 @@
 use quick_xml::{
-    Decoder, Reader,
-    events::{BytesStart, Event},
+    Decoder, NsReader,
+    events::{BytesRef, BytesStart, Event, attributes::Attributes},
+    name::{QName, ResolveResult},
 };
 use rootcause::prelude::*;
-use std::{io::BufRead, path::Path};
+use std::{collections::HashMap, io::BufRead, path::Path};
 use thiserror::Error;
 use tracing::*;
 
@@ -36,90 +37,1613 @@ pub enum SdkError {
     UnknownError,
 }
 
+/// How markup compatibility (ECMA-376 Part 3) constructs are handled when deserializing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarkupCompatibilityMode {
+    /// Replaces each `mc:AlternateContent` block with the content of its first `mc:Choice` whose
+    /// required namespaces are understood, or else of its `mc:Fallback`, and drops elements of
+    /// ignorable namespaces that are not understood.
+    #[default]
+    Process,
+    /// Keeps whole `mc:AlternateContent` blocks verbatim as raw children of their parent element,
+    /// so that they are written back unchanged on save.
+    Preserve,
+}
+
+/// How values that fail to parse are handled when deserializing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Any invalid value or missing required attribute or child element is an error.
+    #[default]
+    Strict,
+    /// Invalid values fall back to `None` and missing required values to their default, each
+    /// being recorded as a [`ParseDiagnostic`].
+    Lenient,
+}
+
+/// Options for deserializing XML.
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    pub markup_compatibility: MarkupCompatibilityMode,
+}
+
+/// The line break written by serializers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NewlineStyle {
+    /// `\r\n`, as written by Office.
+    #[default]
+    CrLf,
+    Lf,
+}
+
+impl NewlineStyle {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            Self::CrLf => "\r\n",
+            Self::Lf => "\n",
+        };
+    }
+}
+
+/// Options for serializing XML. The default is compact output, as read by Office.
+#[derive(Clone, Debug, Default)]
+pub struct SerializeOptions {
+    /// The string each nesting level is indented by, e.g. two spaces, or `None` to write all
+    /// elements on a single line. The content of elements declaring `xml:space="preserve"` is
+    /// never indented.
+    pub indent: Option<String>,
+    /// The line break following the XML declaration and, when indenting, each element.
+    pub newline: NewlineStyle,
+    /// Writes elements without content as `<a/>` instead of `<a></a>`.
+    pub self_close_empty: bool,
+}
+
+impl SerializeOptions {
+    /// Pretty-printed output, indented by two spaces with `\n` line breaks and self-closed empty
+    /// elements.
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some("  ".to_string()),
+            newline: NewlineStyle::Lf,
+            self_close_empty: true,
+        }
+    }
+}
+
+/// Options for saving packages.
+#[derive(Clone, Debug, Default)]
+pub struct SaveOptions {
+    /// Writes parts that are serialized again in the encoding they were read in, instead of
+    /// UTF-8.
+    pub keep_encoding: bool,
+    /// How parts are serialized. Unless compact, the root elements of unmodified parts are
+    /// serialized again instead of being copied verbatim.
+    pub serialize_options: SerializeOptions,
+    /// Writes byte-identical packages for identical content, i.e. entries get a fixed timestamp
+    /// and permissions instead of the current time, including those of unmodified parts, which
+    /// are then compressed again instead of being copied verbatim. Namespace declarations are
+    /// always written in prefix order, and parts in the order of the fields of their parent part
+    /// followed by its unknown parts.
+    pub deterministic: bool,
+}
+
+impl SaveOptions {
+    /// Whether the serialized output differs from the compact default, so that parts copied
+    /// verbatim would be written differently.
+    pub fn reserializes_parts(&self) -> bool {
+        return self.serialize_options.indent.is_some()
+            || self.serialize_options.newline != NewlineStyle::CrLf
+            || self.serialize_options.self_close_empty;
+    }
+
+    /// The options of the zip entries written when saving.
+    #[cfg(feature = "parts")]
+    pub fn zip_file_options(&self) -> zip::write::SimpleFileOptions {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(0o755);
+
+        if self.deterministic {
+            // 1980-01-01, the earliest time a zip entry can hold.
+            return options.last_modified_time(zip::DateTime::default());
+        }
+
+        return options;
+    }
+}
+
+/// A problem recovered from while deserializing in lenient mode.
+#[derive(Clone, Debug, Default)]
+pub struct ParseDiagnostic {
+    /// The qualified names of the element holding the value and its ancestors, joined by `/`.
+    pub element_path: String,
+    /// The qualified name of the attribute, or `None` for the text content or a child element.
+    pub attribute: Option<String>,
+    /// The raw value, or `None` for a missing attribute or child element.
+    pub raw_value: Option<String>,
+    pub reason: String,
+}
+
 pub trait XmlReader<'de> {
     fn next(&mut self) -> Result<Event<'de>, SdkErrorReport>;
     fn decoder(&self) -> Decoder;
+
+    /// Resolves an element name of the last read event into its namespace URI and local name,
+    /// using the `xmlns` declarations in scope.
+    fn resolve_element<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]);
+
+    /// Resolves an attribute name of the last read event into its namespace URI and local name.
+    /// Unprefixed attributes are never bound to a namespace.
+    fn resolve_attribute<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]);
+
+    fn parse_mode(&self) -> ParseMode;
+
+    /// Returns the qualified names of the element of the last read event and its ancestors,
+    /// joined by `/`.
+    fn element_path(&self) -> &str;
+
+    /// Returns the position of the last read event and the path of its element.
+    fn location(&self) -> SourceLocation;
+
+    /// Returns the diagnostics recorded so far in lenient mode.
+    fn diagnostics_mut(&mut self) -> &mut Vec<ParseDiagnostic>;
+
+    /// Returns the parsed value of an attribute or the text content. In lenient mode a parse
+    /// error is recorded as a diagnostic and `None` is returned instead.
+    fn recover<T, E: Into<SdkErrorReport>>(
+        &mut self,
+        result: Result<T, E>,
+        attribute: Option<&str>,
+        raw_value: &[u8],
+    ) -> Result<Option<T>, SdkErrorReport> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if self.parse_mode() == ParseMode::Lenient => {
+                let e: SdkErrorReport = e.into();
+
+                let diagnostic = ParseDiagnostic {
+                    element_path: self.element_path().to_string(),
+                    attribute: attribute.map(str::to_string),
+                    raw_value: Some(String::from_utf8_lossy(raw_value).into_owned()),
+                    reason: e.current_context().to_string(),
+                };
+
+                warn!("Recovered from invalid value: {diagnostic:?}");
+
+                self.diagnostics_mut().push(diagnostic);
+
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Unwraps a required attribute or child element. In lenient mode a missing value is recorded
+    /// as a diagnostic and replaced by its default.
+    fn recover_missing<T: Default>(
+        &mut self,
+        value: Option<T>,
+        attribute: Option<&str>,
+        name: &str,
+    ) -> Result<T, SdkErrorReport> {
+        match value {
+            Some(value) => Ok(value),
+            None if self.parse_mode() == ParseMode::Lenient => {
+                let element_path = self.element_path().to_string();
+
+                // An invalid value is already reported by `recover`.
+                if let Some(last_diagnostic) = self.diagnostics_mut().last()
+                    && last_diagnostic.raw_value.is_some()
+                    && last_diagnostic.element_path == element_path
+                    && last_diagnostic.attribute.as_deref() == attribute
+                {
+                    return Ok(T::default());
+                }
+
+                let diagnostic = ParseDiagnostic {
+                    element_path,
+                    attribute: attribute.map(str::to_string),
+                    raw_value: None,
+                    reason: format!("missing required `{name}`"),
+                };
+
+                warn!("Recovered from missing value: {diagnostic:?}");
+
+                self.diagnostics_mut().push(diagnostic);
+
+                Ok(T::default())
+            }
+            None => Err(SdkError::CommonError(name.to_string()))?,
+        }
+    }
+
+    /// Skips the whole subtree of the element whose start tag was read last, up to and including
+    /// its end tag.
+    fn skip_element(&mut self) -> Result<(), SdkErrorReport> {
+        let mut depth = 0usize;
+
+        loop {
+            match self.next()? {
+                Event::Start(_) => depth += 1,
+                Event::End(_) if depth == 0 => return Ok(()),
+                Event::End(_) => depth -= 1,
+                Event::Eof => {
+                    return Err(SdkError::UnknownError)
+                        .attach("Reached EOF when skipping an element");
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Maps a resolved namespace to its URI, falling back to the well-known namespace of an
+/// undeclared prefix.
+#[inline]
+fn resolved_namespace_uri(resolve_result: ResolveResult<'_>) -> Option<&[u8]> {
+    match resolve_result {
+        ResolveResult::Bound(namespace) => Some(namespace.into_inner()),
+        ResolveResult::Unbound => None,
+        ResolveResult::Unknown(prefix) => namespace::namespace_uri_by_prefix(&prefix),
+    }
+}
+
+const MC_NAMESPACE_URI: &[u8] = b"http://schemas.openxmlformats.org/markup-compatibility/2006";
+
+/// An `mc:AlternateContent` block being read.
+#[derive(Debug)]
+struct AlternateContentFrame {
+    level: usize,
+    selected: bool,
+}
+
+/// Filters the events of a reader according to ECMA-376 Part 3, so that deserializers only see
+/// the selected content of `mc:AlternateContent` blocks and no content of ignored namespaces.
+#[derive(Debug, Default)]
+struct MarkupCompatibilityProcessor {
+    /// The number of elements currently open.
+    depth: usize,
+    /// The level of the element whose subtree is being skipped.
+    skip_level: Option<usize>,
+    alternate_content_stack: Vec<AlternateContentFrame>,
+    /// The ignorable namespaces that are not understood, with the level of the declaring element.
+    ignorable_stack: Vec<(usize, Vec<Vec<u8>>)>,
+}
+
+impl MarkupCompatibilityProcessor {
+    fn from_options(options: &ParseOptions) -> Option<Self> {
+        match options.markup_compatibility {
+            MarkupCompatibilityMode::Process => Some(Self::default()),
+            MarkupCompatibilityMode::Preserve => None,
+        }
+    }
+
+    /// Returns whether the event is passed on to the deserializer.
+    fn accept<R>(&mut self, reader: &NsReader<R>, event: &Event) -> Result<bool, SdkErrorReport> {
+        let (e, level) = match event {
+            Event::Start(e) => {
+                self.depth += 1;
+
+                (e, self.depth - 1)
+            }
+            Event::Empty(e) => (e, self.depth),
+            Event::End(_) => {
+                self.depth = self.depth.saturating_sub(1);
+
+                return Ok(self.accept_end(self.depth));
+            }
+            Event::Eof => return Ok(true),
+            _ => {
+                return Ok(self.skip_level.is_none()
+                    && self
+                        .alternate_content_stack
+                        .last()
+                        .is_none_or(|frame| frame.level + 1 != self.depth));
+            }
+        };
+
+        let empty_tag = matches!(event, Event::Empty(_));
+
+        if self.skip_level.is_some() {
+            return Ok(false);
+        }
+
+        if let Some(frame) = self.alternate_content_stack.last_mut()
+            && frame.level + 1 == level
+        {
+            let (namespace_uri, local_name) = resolve_element_name(reader, e.name());
+
+            let selectable = namespace_uri == Some(MC_NAMESPACE_URI)
+                && match local_name {
+                    b"Choice" => is_choice_understood(reader, e)?,
+                    b"Fallback" => true,
+                    _ => false,
+                };
+
+            if !frame.selected && selectable {
+                frame.selected = true;
+            } else if !empty_tag {
+                self.skip_level = Some(level);
+            }
+
+            return Ok(false);
+        }
+
+        let (namespace_uri, local_name) = resolve_element_name(reader, e.name());
+
+        if namespace_uri == Some(MC_NAMESPACE_URI) && local_name == b"AlternateContent" {
+            if !empty_tag {
+                self.alternate_content_stack.push(AlternateContentFrame {
+                    level,
+                    selected: false,
+                });
+            }
+
+            return Ok(false);
+        }
+
+        if let Some(namespace_uri) = namespace_uri
+            && self.ignorable_stack.iter().any(|(_, namespace_uri_list)| {
+                namespace_uri_list
+                    .iter()
+                    .any(|ignorable| ignorable == namespace_uri)
+            })
+        {
+            if !empty_tag {
+                self.skip_level = Some(level);
+            }
+
+            return Ok(false);
+        }
+
+        if !empty_tag
+            && e.attributes_raw()
+                .windows(b"Ignorable".len())
+                .any(|window| window == b"Ignorable")
+        {
+            let namespace_uri_list = ignorable_namespace_uri_list(reader, e)?;
+
+            if !namespace_uri_list.is_empty() {
+                self.ignorable_stack.push((level, namespace_uri_list));
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn accept_end(&mut self, level: usize) -> bool {
+        if let Some(skip_level) = self.skip_level {
+            if skip_level == level {
+                self.skip_level = None;
+            }
+
+            return false;
+        }
+
+        if let Some(frame) = self.alternate_content_stack.last() {
+            if frame.level + 1 == level {
+                return false;
+            }
+
+            if frame.level == level {
+                self.alternate_content_stack.pop();
+
+                return false;
+            }
+        }
+
+        if self
+            .ignorable_stack
+            .last()
+            .is_some_and(|(ignorable_level, _)| *ignorable_level == level)
+        {
+            self.ignorable_stack.pop();
+        }
+
+        true
+    }
+}
+
+#[inline]
+fn resolve_element_name<'r, 'n, R>(
+    reader: &'r NsReader<R>,
+    name: QName<'n>,
+) -> (Option<&'r [u8]>, &'n [u8]) {
+    let (resolve_result, local_name) = reader.resolve_element(name);
+
+    (
+        resolved_namespace_uri(resolve_result),
+        local_name.into_inner(),
+    )
+}
+
+/// Resolves a whitespace separated list of namespace prefixes into their namespace URIs.
+fn resolve_prefix_list<R>(reader: &NsReader<R>, prefix_list: &[u8]) -> Vec<Vec<u8>> {
+    prefix_list
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|prefix| !prefix.is_empty())
+        .map(|prefix| {
+            let name = [prefix, b":_"].concat();
+
+            resolve_element_name(reader, QName(&name))
+                .0
+                .unwrap_or_default()
+                .to_vec()
+        })
+        .collect()
+}
+
+/// Whether all namespaces listed in the `Requires` attribute of an `mc:Choice` are understood.
+fn is_choice_understood<R>(reader: &NsReader<R>, e: &BytesStart) -> Result<bool, SdkErrorReport> {
+    for attr in e.attributes().with_checks(false) {
+        let attr = attr.map_err(SdkError::from)?;
+
+        if attr.key.as_ref() == b"Requires" {
+            return Ok(resolve_prefix_list(reader, &attr.value)
+                .iter()
+                .all(|namespace_uri| namespace::is_namespace_understood(namespace_uri)));
+        }
+    }
+
+    Ok(false)
+}
+
+/// Returns the namespaces of the `mc:Ignorable` attribute of an element that are not understood.
+fn ignorable_namespace_uri_list<R>(
+    reader: &NsReader<R>,
+    e: &BytesStart,
+) -> Result<Vec<Vec<u8>>, SdkErrorReport> {
+    for attr in e.attributes().with_checks(false) {
+        let attr = attr.map_err(SdkError::from)?;
+
+        let (resolve_result, local_name) = reader.resolve_attribute(attr.key);
+
+        if local_name.as_ref() == b"Ignorable"
+            && resolved_namespace_uri(resolve_result) == Some(MC_NAMESPACE_URI)
+        {
+            let mut namespace_uri_list = resolve_prefix_list(reader, &attr.value);
+
+            namespace_uri_list
+                .retain(|namespace_uri| !namespace::is_namespace_understood(namespace_uri));
+
+            return Ok(namespace_uri_list);
+        }
+    }
+
+    Ok(vec![])
+}
+
+/// The qualified names of the open elements, joined by `/`. Elements following siblings of the
+/// same name carry their 1-based index among them, e.g. `w:tbl[3]`.
+#[derive(Debug)]
+struct ElementPath {
+    path: String,
+    len_stack: Vec<usize>,
+    /// The number of child elements read so far per name, for the document and each open element.
+    sibling_count_stack: Vec<HashMap<String, usize>>,
+    /// Whether the last element is closed, i.e. is popped before the next event.
+    pop_pending: bool,
+}
+
+impl ElementPath {
+    fn new() -> Self {
+        Self {
+            path: String::new(),
+            len_stack: vec![],
+            sibling_count_stack: vec![HashMap::new()],
+            pop_pending: false,
+        }
+    }
+
+    fn update(&mut self, event: &Event) {
+        if self.pop_pending {
+            if let Some(len) = self.len_stack.pop() {
+                self.path.truncate(len);
+                self.sibling_count_stack.pop();
+            }
+
+            self.pop_pending = false;
+        }
+
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                self.len_stack.push(self.path.len());
+
+                if !self.path.is_empty() {
+                    self.path.push('/');
+                }
+
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+
+                self.path.push_str(&name);
+
+                if let Some(sibling_count_map) = self.sibling_count_stack.last_mut() {
+                    let sibling_count = sibling_count_map.entry(name).or_default();
+
+                    *sibling_count += 1;
+
+                    if *sibling_count > 1 {
+                        self.path.push_str(&format!("[{sibling_count}]"));
+                    }
+                }
+
+                self.sibling_count_stack.push(HashMap::new());
+
+                self.pop_pending = matches!(event, Event::Empty(_));
+            }
+            Event::End(_) => self.pop_pending = true,
+            _ => {}
+        }
+    }
+}
+
+/// A position in the XML source together with the path of the element there, attached to the
+/// errors raised while deserializing.
+#[derive(Clone, Debug, Default)]
+pub struct SourceLocation {
+    /// The byte offset of the start of the last read event.
+    pub byte_offset: u64,
+    /// The 1-based line of [`Self::byte_offset`].
+    pub line: u64,
+    /// The 1-based column of [`Self::byte_offset`], in bytes.
+    pub column: u64,
+    /// The qualified names of the element of the last read event and its ancestors, joined by
+    /// `/`.
+    pub element_path: String,
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "At line {}, column {} (byte {}) in [{}]",
+            self.line, self.column, self.byte_offset, self.element_path
+        )
+    }
+}
+
+/// Tracks the line and column of the read events from their raw content.
+#[derive(Debug)]
+struct SourcePosition {
+    byte_offset: u64,
+    line: u64,
+    column: u64,
+    /// The line at the end of the last read event.
+    end_line: u64,
+    /// The byte offset of the start of [`Self::end_line`].
+    end_line_offset: u64,
+}
+
+impl SourcePosition {
+    fn new() -> Self {
+        Self {
+            byte_offset: 0,
+            line: 1,
+            column: 1,
+            end_line: 1,
+            end_line_offset: 0,
+        }
+    }
+
+    /// Updates the position with the event read from `start_offset` to `end_offset`.
+    fn update(&mut self, start_offset: u64, end_offset: u64, event: &Event) {
+        self.byte_offset = start_offset;
+        self.line = self.end_line;
+        self.column = start_offset.saturating_sub(self.end_line_offset) + 1;
+
+        // The content of an event is followed by the markup closing it.
+        let suffix_len = match event {
+            Event::Text(_) => 0,
+            Event::Start(_) | Event::End(_) | Event::DocType(_) | Event::GeneralRef(_) => 1,
+            Event::Empty(_) | Event::Decl(_) | Event::PI(_) => 2,
+            Event::CData(_) | Event::Comment(_) => 3,
+            Event::Eof => return,
+        };
+
+        let content: &[u8] = event;
+
+        if let Some(last_line_feed_index) = content.iter().rposition(|b| *b == b'\n') {
+            self.end_line += content.iter().filter(|b| **b == b'\n').count() as u64;
+
+            self.end_line_offset = (end_offset + last_line_feed_index as u64 + 1)
+                .saturating_sub(suffix_len + content.len() as u64);
+        }
+    }
+}
+
+/// The state shared by the readers besides the underlying quick-xml reader.
+#[derive(Debug)]
+struct ReaderState {
+    mode: ParseMode,
+    markup_compatibility: Option<MarkupCompatibilityProcessor>,
+    element_path: ElementPath,
+    source_position: SourcePosition,
+    diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl ReaderState {
+    fn new(options: &ParseOptions) -> Self {
+        Self {
+            mode: options.mode,
+            markup_compatibility: MarkupCompatibilityProcessor::from_options(options),
+            element_path: ElementPath::new(),
+            source_position: SourcePosition::new(),
+            diagnostics: vec![],
+        }
+    }
+
+    /// Returns whether the event read from `start_offset` is passed on to the deserializer.
+    #[inline]
+    fn accept<R>(
+        &mut self,
+        reader: &NsReader<R>,
+        start_offset: u64,
+        event: &Event,
+    ) -> Result<bool, SdkErrorReport> {
+        self.source_position
+            .update(start_offset, reader.buffer_position(), event);
+
+        if let Some(markup_compatibility) = &mut self.markup_compatibility
+            && !markup_compatibility.accept(reader, event)?
+        {
+            return Ok(false);
+        }
+
+        self.element_path.update(event);
+
+        Ok(true)
+    }
+
+    fn location(&self) -> SourceLocation {
+        SourceLocation {
+            byte_offset: self.source_position.byte_offset,
+            line: self.source_position.line,
+            column: self.source_position.column,
+            element_path: self.element_path.path.clone(),
+        }
+    }
+}
+
+pub struct IoReader<R: BufRead> {
+    reader: NsReader<R>,
+    buf: Vec<u8>,
+    state: ReaderState,
+}
+
+impl<R: BufRead> IoReader<R> {
+    #[inline]
+    pub fn new(reader: NsReader<R>) -> Self {
+        Self::new_with_options(reader, &ParseOptions::default())
+    }
+
+    #[inline]
+    pub fn new_with_options(reader: NsReader<R>, options: &ParseOptions) -> Self {
+        Self {
+            reader,
+            buf: vec![],
+            state: ReaderState::new(options),
+        }
+    }
+
+    /// Returns the diagnostics recorded in lenient mode.
+    pub fn into_diagnostics(self) -> Vec<ParseDiagnostic> { self.state.diagnostics }
+}
+
+impl<'de, R: BufRead> XmlReader<'de> for IoReader<R> {
+    #[inline]
+    fn next(&mut self) -> Result<Event<'de>, SdkErrorReport> {
+        loop {
+            self.buf.clear();
+
+            let start_offset = self.reader.buffer_position();
+
+            let event = self
+                .reader
+                .read_event_into(&mut self.buf)
+                .map_err(SdkError::from)?
+                .into_owned();
+
+            if self.state.accept(&self.reader, start_offset, &event)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    #[inline]
+    fn decoder(&self) -> Decoder { self.reader.decoder() }
+
+    #[inline]
+    fn resolve_element<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]) {
+        resolve_element_name(&self.reader, name)
+    }
+
+    #[inline]
+    fn resolve_attribute<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]) {
+        let (resolve_result, local_name) = self.reader.resolve_attribute(name);
+
+        (
+            resolved_namespace_uri(resolve_result),
+            local_name.into_inner(),
+        )
+    }
+
+    #[inline]
+    fn parse_mode(&self) -> ParseMode { self.state.mode }
+
+    #[inline]
+    fn element_path(&self) -> &str { &self.state.element_path.path }
+
+    #[inline]
+    fn location(&self) -> SourceLocation { self.state.location() }
+
+    #[inline]
+    fn diagnostics_mut(&mut self) -> &mut Vec<ParseDiagnostic> { &mut self.state.diagnostics }
+}
+
+pub struct SliceReader<'de> {
+    reader: NsReader<&'de [u8]>,
+    /// The input not read yet when the reader was wrapped, starting at `input_position`.
+    input: &'de [u8],
+    input_position: u64,
+    state: ReaderState,
+}
+
+impl<'de> SliceReader<'de> {
+    #[inline]
+    pub fn new(reader: NsReader<&'de [u8]>) -> Self {
+        Self::new_with_options(reader, &ParseOptions::default())
+    }
+
+    #[inline]
+    pub fn new_with_options(reader: NsReader<&'de [u8]>, options: &ParseOptions) -> Self {
+        Self {
+            input: *reader.get_ref(),
+            input_position: reader.buffer_position(),
+            reader,
+            state: ReaderState::new(options),
+        }
+    }
+
+    /// Returns the diagnostics recorded in lenient mode.
+    pub fn into_diagnostics(self) -> Vec<ParseDiagnostic> { self.state.diagnostics }
+
+    /// Returns the given bytes as a slice of the input, if they are part of it, as is the content
+    /// of the events read by this reader.
+    fn input_slice(&self, bytes: &[u8]) -> Option<&'de [u8]> {
+        let offset = (bytes.as_ptr() as usize).checked_sub(self.input.as_ptr() as usize)?;
+
+        return self
+            .input
+            .get(offset..offset + bytes.len())
+            .filter(|slice| slice.as_ptr() == bytes.as_ptr());
+    }
+
+    /// Iterates over the attributes of a start tag read by this reader, borrowing them from the
+    /// input.
+    pub fn attributes(&self, e: &BytesStart<'de>) -> Result<Attributes<'de>, SdkErrorReport> {
+        let content = self.input_slice(e).ok_or_else(|| {
+            SdkError::CommonError("start tag not read from the input".to_string())
+        })?;
+
+        let content =
+            std::str::from_utf8(content).map_err(|e| SdkError::CommonError(e.to_string()))?;
+
+        let mut attributes = Attributes::new(content, e.name().as_ref().len());
+
+        attributes.with_checks(false);
+
+        Ok(attributes)
+    }
+
+    /// Reads an element and its whole subtree, returning its markup as a slice of the input.
+    pub fn read_raw_str(
+        &mut self,
+        e: &BytesStart<'de>,
+        empty_tag: bool,
+    ) -> Result<&'de str, SdkErrorReport> {
+        let content = self.input_slice(e).ok_or_else(|| {
+            SdkError::CommonError("start tag not read from the input".to_string())
+        })?;
+
+        // The content of a start tag follows its `<`.
+        let start = content.as_ptr() as usize - self.input.as_ptr() as usize - 1;
+
+        if !empty_tag {
+            self.skip_element()?;
+        }
+
+        let end = (self.reader.buffer_position() - self.input_position) as usize;
+
+        Ok(std::str::from_utf8(&self.input[start..end])
+            .map_err(|e| SdkError::CommonError(e.to_string()))?)
+    }
+}
+
+impl<'de> XmlReader<'de> for SliceReader<'de> {
+    #[inline]
+    fn next(&mut self) -> Result<Event<'de>, SdkErrorReport> {
+        loop {
+            let start_offset = self.reader.buffer_position();
+
+            let event = self.reader.read_event().map_err(SdkError::from)?;
+
+            if self.state.accept(&self.reader, start_offset, &event)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    #[inline]
+    fn decoder(&self) -> Decoder { self.reader.decoder() }
+
+    #[inline]
+    fn resolve_element<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]) {
+        resolve_element_name(&self.reader, name)
+    }
+
+    #[inline]
+    fn resolve_attribute<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]) {
+        let (resolve_result, local_name) = self.reader.resolve_attribute(name);
+
+        (
+            resolved_namespace_uri(resolve_result),
+            local_name.into_inner(),
+        )
+    }
+
+    #[inline]
+    fn parse_mode(&self) -> ParseMode { self.state.mode }
+
+    #[inline]
+    fn element_path(&self) -> &str { &self.state.element_path.path }
+
+    #[inline]
+    fn location(&self) -> SourceLocation { self.state.location() }
+
+    #[inline]
+    fn diagnostics_mut(&mut self) -> &mut Vec<ParseDiagnostic> { &mut self.state.diagnostics }
+}
+
+/// The character encoding of an XML document, detected from its byte order mark or the encoding
+/// declared by its XML declaration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum XmlEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1.
+    Latin1,
+    Windows1252,
+}
+
+/// The characters of the bytes `0x80..=0x9F` in windows-1252, the other bytes being the same as
+/// in ISO-8859-1. Unassigned bytes map to the C1 control character of the same value.
+const WINDOWS_1252_CHARS: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+impl XmlEncoding {
+    /// The name written to the XML declaration.
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le | Self::Utf16Be => "UTF-16",
+            Self::Latin1 => "ISO-8859-1",
+            Self::Windows1252 => "windows-1252",
+        };
+    }
+
+    /// Detects the encoding from the start of a document, which has to hold the whole XML
+    /// declaration, if any.
+    pub fn detect(bytes: &[u8]) -> Result<Self, SdkErrorReport> {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => return Ok(Self::Utf8),
+            [0xFF, 0xFE, ..] | [b'<', 0, b'?', 0, ..] => return Ok(Self::Utf16Le),
+            [0xFE, 0xFF, ..] | [0, b'<', 0, b'?', ..] => return Ok(Self::Utf16Be),
+            _ => {}
+        }
+
+        let Some(encoding_name) = declared_encoding_name(bytes) else {
+            return Ok(Self::Utf8);
+        };
+
+        // The bytes seen so far are ASCII, so a declared UTF-16 cannot be right.
+        return match encoding_name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" | "us-ascii" | "ascii" | "utf-16" | "utf-16le" | "utf-16be" => {
+                Ok(Self::Utf8)
+            }
+            "iso-8859-1" | "iso_8859-1" | "latin1" | "latin-1" | "l1" => Ok(Self::Latin1),
+            "windows-1252" | "cp1252" => Ok(Self::Windows1252),
+            _ => Err(SdkError::CommonError(format!("unsupported encoding {encoding_name}")).into()),
+        };
+    }
+}
+
+/// Returns the value of the `encoding` pseudo-attribute of an ASCII compatible XML declaration.
+fn declared_encoding_name(bytes: &[u8]) -> Option<&str> {
+    let declaration = bytes.strip_prefix(b"<?xml")?;
+
+    let declaration = &declaration[..declaration.windows(2).position(|w| w == b"?>")?];
+
+    let declaration = std::str::from_utf8(declaration).ok()?;
+
+    let (_, value) = declaration.split_once("encoding")?;
+
+    let value = value.trim_start().strip_prefix('=')?.trim_start();
+
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+
+    return value[1..].split(quote).next();
+}
+
+/// Sets the `encoding` pseudo-attribute of the XML declaration, adding the declaration if
+/// missing.
+fn set_declared_encoding(xml: &str, encoding_name: &str) -> String {
+    let xml = xml.trim_start_matches('\u{feff}');
+
+    let Some(declaration_end) = xml.starts_with("<?xml").then(|| xml.find("?>")).flatten() else {
+        return format!("<?xml version=\"1.0\" encoding=\"{encoding_name}\"?>{xml}");
+    };
+
+    let declaration = &xml[..declaration_end];
+
+    let value_range = declaration.find("encoding").and_then(|encoding_start| {
+        let value_start = encoding_start + declaration[encoding_start..].find(['"', '\''])? + 1;
+        let value_end = value_start + declaration[value_start..].find(['"', '\''])?;
+
+        Some(value_start..value_end)
+    });
+
+    if let Some(value_range) = value_range {
+        return format!(
+            "{}{encoding_name}{}",
+            &xml[..value_range.start],
+            &xml[value_range.end..]
+        );
+    }
+
+    // The encoding declaration follows the version one, which is the only required.
+    let insert_index = declaration
+        .find("version")
+        .and_then(|version_start| {
+            let value_start = version_start + declaration[version_start..].find(['"', '\''])? + 1;
+
+            Some(value_start + declaration[value_start..].find(['"', '\''])? + 1)
+        })
+        .unwrap_or("<?xml".len());
+
+    return format!(
+        "{} encoding=\"{encoding_name}\"{}",
+        &xml[..insert_index],
+        &xml[insert_index..]
+    );
+}
+
+/// Decodes an XML document in any supported encoding. Documents not in UTF-8 get their XML
+/// declaration changed to UTF-8, matching the returned string.
+pub fn decode_xml_bytes(bytes: &[u8]) -> Result<(String, XmlEncoding), SdkErrorReport> {
+    let encoding = XmlEncoding::detect(bytes)?;
+
+    let xml = match encoding {
+        XmlEncoding::Utf8 => {
+            let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+
+            return Ok((
+                String::from_utf8(bytes.to_vec())
+                    .map_err(|e| SdkError::CommonError(e.to_string()))?,
+                encoding,
+            ));
+        }
+        XmlEncoding::Utf16Le | XmlEncoding::Utf16Be => {
+            if !bytes.len().is_multiple_of(2) {
+                return Err(SdkError::CommonError("odd UTF-16 length".to_string()).into());
+            }
+
+            let code_units = bytes.chunks_exact(2).map(|chunk| {
+                if encoding == XmlEncoding::Utf16Le {
+                    u16::from_le_bytes([chunk[0], chunk[1]])
+                } else {
+                    u16::from_be_bytes([chunk[0], chunk[1]])
+                }
+            });
+
+            char::decode_utf16(code_units)
+                .collect::<Result<String, _>>()
+                .map_err(|e| SdkError::CommonError(e.to_string()))?
+        }
+        XmlEncoding::Latin1 => bytes.iter().map(|b| *b as char).collect(),
+        XmlEncoding::Windows1252 => bytes
+            .iter()
+            .map(|b| match b {
+                0x80..=0x9F => WINDOWS_1252_CHARS[(b - 0x80) as usize],
+                _ => *b as char,
+            })
+            .collect(),
+    };
+
+    return Ok((
+        set_declared_encoding(&xml, XmlEncoding::Utf8.name()),
+        encoding,
+    ));
+}
+
+/// Encodes an XML document, declaring the encoding in its XML declaration. Characters the
+/// encoding cannot represent are written as character references.
+pub fn encode_xml_string(xml: &str, encoding: XmlEncoding) -> Vec<u8> {
+    if encoding == XmlEncoding::Utf8 {
+        return xml.as_bytes().to_vec();
+    }
+
+    let xml = set_declared_encoding(xml, encoding.name());
+
+    return match encoding {
+        XmlEncoding::Utf8 => xml.into_bytes(),
+        XmlEncoding::Utf16Le => [0xFF, 0xFE]
+            .into_iter()
+            .chain(xml.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        XmlEncoding::Utf16Be => [0xFE, 0xFF]
+            .into_iter()
+            .chain(xml.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+        XmlEncoding::Latin1 | XmlEncoding::Windows1252 => {
+            let mut bytes = Vec::with_capacity(xml.len());
+
+            for c in xml.chars() {
+                let byte = match c as u32 {
+                    0x80..=0x9F if encoding == XmlEncoding::Windows1252 => None,
+                    code_point @ 0..=0xFF => Some(code_point as u8),
+                    _ if encoding == XmlEncoding::Windows1252 => WINDOWS_1252_CHARS
+                        .iter()
+                        .position(|windows_1252_char| *windows_1252_char == c)
+                        .map(|index| 0x80 + index as u8),
+                    _ => None,
+                };
+
+                match byte {
+                    Some(byte) => bytes.push(byte),
+                    None => bytes.extend_from_slice(format!("&#{};", c as u32).as_bytes()),
+                }
+            }
+
+            bytes
+        }
+    };
+}
+
+pub trait Deserializeable: Sized {
+    fn from_str(str: impl AsRef<str>) -> Result<Self, SdkErrorReport> {
+        Ok(Self::from_str_with_options(str, &ParseOptions::default())?.0)
+    }
+
+    /// Deserializes with the given options, returning the diagnostics recorded in lenient mode
+    /// alongside the value.
+    fn from_str_with_options(
+        str: impl AsRef<str>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseDiagnostic>), SdkErrorReport> {
+        let mut xml_reader = quick_xml::NsReader::from_str(str.as_ref());
+        xml_reader.config_mut().check_end_names = false;
+        xml_reader.config_mut().trim_text(false);
+
+        let mut xml_reader = SliceReader::new_with_options(xml_reader, options);
+
+        let value =
+            Self::deserialize_inner(&mut xml_reader, None).attach_with(|| xml_reader.location())?;
+
+        Ok((value, xml_reader.into_diagnostics()))
+    }
+
+    fn from_reader(reader: impl BufRead) -> Result<Self, SdkErrorReport> {
+        Ok(Self::from_reader_with_options(reader, &ParseOptions::default())?.0)
+    }
+
+    /// Deserializes with the given options, returning the diagnostics recorded in lenient mode
+    /// alongside the value.
+    fn from_reader_with_options(
+        mut reader: impl BufRead,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseDiagnostic>), SdkErrorReport> {
+        // Documents in other encodings than UTF-8 are decoded up front.
+        if XmlEncoding::detect(reader.fill_buf().map_err(SdkError::from)?)? != XmlEncoding::Utf8 {
+            let mut bytes = vec![];
+
+            reader.read_to_end(&mut bytes).map_err(SdkError::from)?;
+
+            return Self::from_str_with_options(decode_xml_bytes(&bytes)?.0, options);
+        }
+
+        let mut xml_reader = quick_xml::NsReader::from_reader(reader);
+        xml_reader.config_mut().check_end_names = false;
+        xml_reader.config_mut().trim_text(false);
+
+        let mut xml_reader = IoReader::new_with_options(xml_reader, options);
+
+        let value =
+            Self::deserialize_inner(&mut xml_reader, None).attach_with(|| xml_reader.location())?;
+
+        Ok((value, xml_reader.into_diagnostics()))
+    }
+
+    fn from_file(path: impl AsRef<Path>) -> Result<Self, SdkErrorReport> {
+        Ok(Self::from_file_with_options(path, &ParseOptions::default())?.0)
+    }
+
+    /// Deserializes with the given options, returning the diagnostics recorded in lenient mode
+    /// alongside the value.
+    fn from_file_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseDiagnostic>), SdkErrorReport> {
+        Self::from_reader_with_options(
+            std::io::BufReader::new(std::fs::File::open(path).map_err(SdkError::from)?),
+            options,
+        )
+    }
+
+    fn deserialize_inner<'de>(
+        xml_reader: &mut impl XmlReader<'de>,
+        xml_event: Option<(BytesStart<'de>, bool)>,
+    ) -> Result<Self, SdkErrorReport>;
 }
 
-pub struct IoReader<R: BufRead> {
-    reader: Reader<R>,
-    buf: Vec<u8>,
+/// Deserializes the borrowed representation of a schema type, whose strings are borrowed from the
+/// input where possible.
+#[cfg(feature = "borrowed")]
+pub trait BorrowedDeserializeable<'de>: Sized {
+    fn from_str_borrowed(str: &'de str) -> Result<Self, SdkErrorReport> {
+        Ok(Self::from_str_borrowed_with_options(str, &ParseOptions::default())?.0)
+    }
+
+    /// Deserializes with the given options, returning the diagnostics recorded in lenient mode
+    /// alongside the value.
+    fn from_str_borrowed_with_options(
+        str: &'de str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseDiagnostic>), SdkErrorReport> {
+        let mut xml_reader = quick_xml::NsReader::from_str(str);
+        xml_reader.config_mut().check_end_names = false;
+        xml_reader.config_mut().trim_text(false);
+
+        let mut xml_reader = SliceReader::new_with_options(xml_reader, options);
+
+        let value = Self::deserialize_borrowed(&mut xml_reader, None)
+            .attach_with(|| xml_reader.location())?;
+
+        Ok((value, xml_reader.into_diagnostics()))
+    }
+
+    fn deserialize_borrowed(
+        xml_reader: &mut SliceReader<'de>,
+        xml_event: Option<(BytesStart<'de>, bool)>,
+    ) -> Result<Self, SdkErrorReport>;
 }
 
-impl<R: BufRead> IoReader<R> {
+/// Links a schema type to its borrowed representation in `crate::borrowed`.
+#[cfg(feature = "borrowed")]
+pub trait HasBorrowed {
+    type Borrowed<'de>: BorrowedDeserializeable<'de>;
+}
+
+/// An attribute not known to the schema, kept with its qualified name and unescaped value.
+#[derive(Clone, Debug, Default)]
+pub struct RawAttribute {
+    pub name: String,
+    pub value: String,
+}
+
+/// Child markup kept verbatim, i.e. an element not known to the schema or a preserved
+/// `mc:AlternateContent` block.
+#[derive(Clone, Debug, Default)]
+pub struct RawChild {
+    /// The number of known children preceding the markup.
+    pub position: usize,
+    pub xml: String,
+}
+
+/// An attribute not known to the schema, borrowed from the input where possible.
+#[cfg(feature = "borrowed")]
+#[derive(Clone, Debug, Default)]
+pub struct BorrowedRawAttribute<'de> {
+    pub name: &'de str,
+    pub value: std::borrow::Cow<'de, str>,
+}
+
+/// Child markup kept verbatim as a slice of the input.
+#[cfg(feature = "borrowed")]
+#[derive(Clone, Debug, Default)]
+pub struct BorrowedRawChild<'de> {
+    /// The number of known children preceding the markup.
+    pub position: usize,
+    pub xml: &'de str,
+}
+
+/// Reads an element and its whole subtree, returning its markup verbatim.
+pub fn read_raw_xml<'de>(
+    xml_reader: &mut impl XmlReader<'de>,
+    e: BytesStart<'de>,
+    empty_tag: bool,
+) -> Result<String, SdkErrorReport> {
+    let mut writer = quick_xml::Writer::new(Vec::new());
+
+    if empty_tag {
+        writer
+            .write_event(Event::Empty(e))
+            .map_err(SdkError::from)?;
+    } else {
+        writer
+            .write_event(Event::Start(e))
+            .map_err(SdkError::from)?;
+
+        let mut depth = 0usize;
+
+        loop {
+            let event = xml_reader.next()?;
+
+            match &event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) if depth == 0 => {
+                    writer.write_event(event).map_err(SdkError::from)?;
+
+                    break;
+                }
+                Event::End(_) => depth -= 1,
+                Event::Eof => Err(SdkError::UnknownError)?,
+                _ => {}
+            }
+
+            writer.write_event(event).map_err(SdkError::from)?;
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner()).map_err(|e| SdkError::CommonError(e.to_string()))?)
+}
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>";
+
+/// Writes serialized XML straight to an [`std::io::Write`], without building intermediate
+/// strings.
+///
+/// Start tags are left open until the content of the element is known, so that empty elements can
+/// be self-closed. Whitespace is only added between elements, never inside text, and not at all
+/// within elements declaring `xml:space="preserve"`.
+pub struct XmlWriter<W: std::io::Write> {
+    inner: W,
+    options: SerializeOptions,
+    /// The number of elements opened and not closed yet.
+    depth: usize,
+    /// Whether the `>` of the last start tag is still to be written.
+    start_tag_open: bool,
+    /// Whether the current element has child elements, i.e. its end tag goes on its own line.
+    has_child_elements: bool,
+    /// The depth of the outermost element declaring `xml:space="preserve"`, if any is open.
+    preserve_space_depth: Option<usize>,
+}
+
+impl<W: std::io::Write> XmlWriter<W> {
+    #[inline]
+    pub fn new(inner: W) -> Self { Self::new_with_options(inner, &SerializeOptions::default()) }
+
     #[inline]
-    pub fn new(reader: Reader<R>) -> Self {
+    pub fn new_with_options(inner: W, options: &SerializeOptions) -> Self {
         Self {
-            reader,
-            buf: vec![],
+            inner,
+            options: options.clone(),
+            depth: 0,
+            start_tag_open: false,
+            has_child_elements: false,
+            preserve_space_depth: None,
         }
     }
-}
 
-impl<'de, R: BufRead> XmlReader<'de> for IoReader<R> {
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    pub fn get_mut(&mut self) -> &mut W { &mut self.inner }
+
+    pub fn into_inner(self) -> W { self.inner }
+
+    /// Writes markup as is.
     #[inline]
-    fn next(&mut self) -> Result<Event<'de>, SdkErrorReport> {
-        self.buf.clear();
+    pub fn write_str(&mut self, str: &str) -> std::io::Result<()> {
+        self.inner.write_all(str.as_bytes())
+    }
 
-        Ok(self
-            .reader
-            .read_event_into(&mut self.buf)
-            .map_err(SdkError::from)?
-            .into_owned())
+    /// Writes the line break of [`SerializeOptions::newline`].
+    #[inline]
+    pub fn write_newline(&mut self) -> std::io::Result<()> {
+        self.write_str(self.options.newline.as_str())
     }
 
+    /// Writes the `Display` output of a value, escaping the characters special to XML.
     #[inline]
-    fn decoder(&self) -> Decoder { self.reader.decoder() }
-}
+    fn write_escaped(&mut self, value: impl std::fmt::Display) -> std::io::Result<()> {
+        use std::fmt::Write;
 
-pub struct SliceReader<'de> {
-    reader: Reader<&'de [u8]>,
-}
+        let mut escape_writer = EscapeWriter {
+            inner: &mut self.inner,
+            result: Ok(()),
+        };
 
-impl<'de> SliceReader<'de> {
+        if write!(escape_writer, "{value}").is_err() {
+            return match escape_writer.result {
+                Ok(()) => Err(std::io::Error::other(std::fmt::Error)),
+                Err(e) => Err(e),
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Writes the `>` of the last start tag, if still open.
     #[inline]
-    pub fn new(reader: Reader<&'de [u8]>) -> Self { Self { reader } }
-}
+    fn close_start_tag(&mut self) -> std::io::Result<()> {
+        if self.start_tag_open {
+            self.start_tag_open = false;
 
-impl<'de> XmlReader<'de> for SliceReader<'de> {
+            self.write_str(">")?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts a new line indented to the current depth, if indenting.
     #[inline]
-    fn next(&mut self) -> Result<Event<'de>, SdkErrorReport> {
-        Ok(self.reader.read_event().map_err(SdkError::from)?)
+    fn write_indent(&mut self) -> std::io::Result<()> {
+        if self.preserve_space_depth.is_some() {
+            return Ok(());
+        }
+
+        let Some(indent) = &self.options.indent else {
+            return Ok(());
+        };
+
+        self.inner
+            .write_all(self.options.newline.as_str().as_bytes())?;
+
+        for _ in 0..self.depth {
+            self.inner.write_all(indent.as_bytes())?;
+        }
+
+        Ok(())
     }
 
+    /// Writes `<name`, leaving the start tag open for attributes.
     #[inline]
-    fn decoder(&self) -> Decoder { self.reader.decoder() }
+    pub fn write_start_tag(&mut self, name: &str) -> std::io::Result<()> {
+        self.close_start_tag()?;
+
+        if self.depth > 0 {
+            self.has_child_elements = true;
+
+            self.write_indent()?;
+        }
+
+        self.write_str("<")?;
+        self.write_str(name)?;
+
+        self.depth += 1;
+        self.start_tag_open = true;
+        self.has_child_elements = false;
+
+        Ok(())
+    }
+
+    /// Writes the end tag of the current element, or self-closes its start tag if it is empty and
+    /// [`SerializeOptions::self_close_empty`] is set.
+    #[inline]
+    pub fn write_end_tag(&mut self, name: &str) -> std::io::Result<()> {
+        self.depth = self.depth.saturating_sub(1);
+
+        if self.start_tag_open && self.options.self_close_empty {
+            self.start_tag_open = false;
+
+            self.write_str("/>")?;
+        } else {
+            self.close_start_tag()?;
+
+            if self.has_child_elements {
+                self.write_indent()?;
+            }
+
+            self.write_str("</")?;
+            self.write_str(name)?;
+            self.write_str(">")?;
+        }
+
+        if self.preserve_space_depth == Some(self.depth) {
+            self.preserve_space_depth = None;
+        }
+
+        self.has_child_elements = true;
+
+        Ok(())
+    }
+
+    /// Writes the escaped `Display` output of a value as text content.
+    #[inline]
+    pub fn write_text(&mut self, value: impl std::fmt::Display) -> std::io::Result<()> {
+        self.close_start_tag()?;
+
+        self.write_escaped(value)
+    }
+
+    /// Remembers that the element being started preserves whitespace.
+    #[inline]
+    fn check_space_preserve(&mut self, key: &str, value: &str) {
+        if key == "xml:space" && value == "preserve" && self.preserve_space_depth.is_none() {
+            self.preserve_space_depth = Some(self.depth.saturating_sub(1));
+        }
+    }
+
+    /// Writes ` key="value"` with the value written as is.
+    #[inline]
+    pub fn write_attribute(&mut self, key: &str, value: &str) -> std::io::Result<()> {
+        self.check_space_preserve(key, value);
+
+        self.write_str(" ")?;
+        self.write_str(key)?;
+        self.write_str("=\"")?;
+        self.write_str(value)?;
+        self.write_str("\"")
+    }
+
+    /// Writes ` key="value"` with the value escaped.
+    #[inline]
+    pub fn write_escaped_attribute(
+        &mut self,
+        key: &str,
+        value: impl std::fmt::Display,
+    ) -> std::io::Result<()> {
+        if key == "xml:space" {
+            self.check_space_preserve(key, &value.to_string());
+        }
+
+        self.write_str(" ")?;
+        self.write_str(key)?;
+        self.write_str("=\"")?;
+        self.write_escaped(value)?;
+        self.write_str("\"")
+    }
+
+    /// Writes the ` xmlns:prefix="uri"` declaration of a namespace prefix.
+    #[inline]
+    pub fn write_xmlns_attribute(&mut self, prefix: &str, uri: &str) -> std::io::Result<()> {
+        self.write_str(" xmlns:")?;
+        self.write_str(prefix)?;
+        self.write_str("=\"")?;
+        self.write_str(uri)?;
+        self.write_str("\"")
+    }
+
+    /// Writes the raw children positioned within the given range of known child indexes.
+    #[inline]
+    pub fn write_raw_children(
+        &mut self,
+        raw_children: &[RawChild],
+        positions: impl std::ops::RangeBounds<usize>,
+    ) -> std::io::Result<()> {
+        for raw_child in raw_children {
+            if positions.contains(&raw_child.position) {
+                self.close_start_tag()?;
+
+                self.has_child_elements = true;
+
+                self.write_indent()?;
+
+                self.write_str(&raw_child.xml)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
-pub trait Deserializeable: Sized {
-    fn from_str(str: impl AsRef<str>) -> Result<Self, SdkErrorReport> {
-        let mut xml_reader = quick_xml::Reader::from_str(str.as_ref());
-        xml_reader.config_mut().check_end_names = false;
-        xml_reader.config_mut().trim_text(false);
+/// Escapes formatted output on its way to an [`std::io::Write`], keeping the I/O error that
+/// `std::fmt::Write` cannot carry.
+struct EscapeWriter<'a, W: std::io::Write> {
+    inner: &'a mut W,
+    result: std::io::Result<()>,
+}
 
-        Self::deserialize_inner(&mut SliceReader::new(xml_reader), None)
+impl<W: std::io::Write> std::fmt::Write for EscapeWriter<'_, W> {
+    fn write_str(&mut self, str: &str) -> std::fmt::Result {
+        let mut unescaped_start = 0;
+
+        for (index, byte) in str.bytes().enumerate() {
+            let escaped: &[u8] = match byte {
+                b'<' => b"&lt;",
+                b'>' => b"&gt;",
+                b'&' => b"&amp;",
+                b'\'' => b"&apos;",
+                b'"' => b"&quot;",
+                _ => continue,
+            };
+
+            self.result = self
+                .inner
+                .write_all(&str.as_bytes()[unescaped_start..index])
+                .and_then(|()| self.inner.write_all(escaped));
+
+            if self.result.is_err() {
+                return Err(std::fmt::Error);
+            }
+
+            unescaped_start = index + 1;
+        }
+
+        self.result = self.inner.write_all(&str.as_bytes()[unescaped_start..]);
+
+        if self.result.is_err() {
+            return Err(std::fmt::Error);
+        }
+
+        Ok(())
     }
+}
 
-    fn from_reader(reader: impl BufRead) -> Result<Self, SdkErrorReport> {
-        let mut xml_reader = quick_xml::Reader::from_reader(reader);
-        xml_reader.config_mut().check_end_names = false;
-        xml_reader.config_mut().trim_text(false);
+/// Runs a write into a buffer, returning the written markup.
+#[inline]
+fn write_xml_string(
+    write: impl FnOnce(&mut XmlWriter<&mut Vec<u8>>) -> std::io::Result<()>,
+) -> String {
+    let mut buffer = Vec::with_capacity(128);
+
+    let mut writer = XmlWriter::new(&mut buffer);
 
-        Self::deserialize_inner(&mut IoReader::new(xml_reader), None)
+    // Writing into a `Vec` does not fail.
+    let _ = write(&mut writer).and_then(|()| writer.close_start_tag());
+
+    return xml_string_from_bytes(buffer);
+}
+
+/// Only strings are written by the serializers, so their output is valid UTF-8.
+#[inline]
+fn xml_string_from_bytes(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(xml) => xml,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
     }
+}
 
-    fn from_file(path: impl AsRef<Path>) -> Result<Self, SdkErrorReport> {
-        let mut xml_reader = quick_xml::Reader::from_file(path).map_err(SdkError::from)?;
-        xml_reader.config_mut().check_end_names = false;
-        xml_reader.config_mut().trim_text(false);
+/// Adds the prefix of a qualified name, if any, to `prefixes`.
+#[inline]
+pub fn insert_xml_prefix<'a>(prefixes: &mut std::collections::BTreeSet<&'a str>, name: &'a str) {
+    if let Some((prefix, _)) = name.split_once(':') {
+        prefixes.insert(prefix);
+    }
+}
+
+/// Declares the known namespaces of the prefixes used within `element` which are not already
+/// declared, so elements built in code serialize to a well-formed part.
+pub fn write_used_xmlns_attributes<W: std::io::Write, T: Serializeable + ?Sized>(
+    writer: &mut XmlWriter<W>,
+    element: &T,
+    is_declared: impl Fn(&str) -> bool,
+) -> std::io::Result<()> {
+    let mut prefixes = std::collections::BTreeSet::new();
+
+    element.collect_xml_prefixes(&mut prefixes);
+
+    for prefix in prefixes {
+        if prefix == "xml" || prefix == "xmlns" || is_declared(prefix) {
+            continue;
+        }
+
+        let Some(namespace_uri) = namespace::namespace_uri_by_prefix(prefix.as_bytes()) else {
+            continue;
+        };
 
-        Self::deserialize_inner(&mut IoReader::new(xml_reader), None)
+        if let Ok(namespace_uri) = std::str::from_utf8(namespace_uri) {
+            writer.write_xmlns_attribute(prefix, namespace_uri)?;
+        }
     }
 
-    fn deserialize_inner<'de>(
-        xml_reader: &mut impl XmlReader<'de>,
-        xml_event: Option<(BytesStart<'de>, bool)>,
-    ) -> Result<Self, SdkErrorReport>;
+    Ok(())
 }
 
 pub trait Serializeable {
@@ -127,84 +1651,152 @@ pub trait Serializeable {
 
     const NAME: &str;
 
-    fn xml_tag_attributes(&self, with_xmlns: bool) -> Option<String>;
+    /// Writes the attributes of the start tag, each preceded by a space.
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()>;
 
-    fn xml_inner(&self, with_xmlns: bool) -> Option<String>;
+    /// Writes the content between the start and end tags.
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()>;
 
+    /// Writes the start tag, which is left open until the content or end tag is written.
     #[inline]
-    fn xml_tag_start(&self, with_xmlns: bool) -> String {
-        let mut xml = String::with_capacity(const { Self::PREFIXED_NAME.len() + 32 });
+    fn write_xml_tag_start<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        if with_xmlns {
+            writer.write_start_tag(Self::PREFIXED_NAME)?;
+        } else {
+            writer.write_start_tag(Self::NAME)?;
+        }
 
-        xml.push('<');
+        self.write_xml_tag_attributes(writer, with_xmlns)
+    }
 
+    #[inline]
+    fn write_xml_tag_end<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
         if with_xmlns {
-            xml.push_str(Self::PREFIXED_NAME);
+            writer.write_end_tag(Self::PREFIXED_NAME)
         } else {
-            xml.push_str(Self::NAME);
+            writer.write_end_tag(Self::NAME)
         }
+    }
 
-        if let Some(xml_tag_attributes) = self.xml_tag_attributes(with_xmlns) {
-            xml.push_str(&xml_tag_attributes);
-        }
+    /// Writes the element, i.e. its start tag, content and end tag.
+    #[inline]
+    fn write_xml_element<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        self.write_xml_tag_start(writer, with_xmlns)?;
 
-        xml.push('>');
+        self.write_xml_inner(writer, with_xmlns)?;
 
-        return xml;
+        self.write_xml_tag_end(writer, with_xmlns)
     }
 
-    #[inline]
-    fn xml_tag_end(&self, with_xmlns: bool) -> String {
-        let mut xml = String::with_capacity(const { Self::PREFIXED_NAME.len() + 3 });
+    /// Adds the namespace prefixes used by the element and its descendants to `prefixes`.
+    fn collect_xml_prefixes<'a>(&'a self, prefixes: &mut std::collections::BTreeSet<&'a str>) {
+        insert_xml_prefix(prefixes, Self::PREFIXED_NAME);
+    }
 
-        xml.push_str("</");
+    /// Serializes straight into `writer`, e.g. a file or a zip entry.
+    fn write_xml<W: std::io::Write>(
+        &self,
+        writer: W,
+        header: bool,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        self.write_xml_with_options(writer, header, with_xmlns, &SerializeOptions::default())
+    }
 
-        if with_xmlns {
-            xml.push_str(Self::PREFIXED_NAME);
-        } else {
-            xml.push_str(Self::NAME);
-        }
+    /// Serializes straight into `writer` with the given options, e.g. pretty-printed.
+    fn write_xml_with_options<W: std::io::Write>(
+        &self,
+        writer: W,
+        header: bool,
+        with_xmlns: bool,
+        options: &SerializeOptions,
+    ) -> std::io::Result<()> {
+        let mut writer = XmlWriter::new_with_options(writer, options);
 
-        xml.push('>');
+        if header {
+            writer.write_str(XML_HEADER)?;
+            writer.write_newline()?;
+        }
 
-        return xml;
+        self.write_xml_element(&mut writer, with_xmlns)
     }
 
     #[inline]
-    fn to_xml_string(&self, header: bool, with_xmlns: bool) -> String {
-        let mut xml = String::with_capacity(64);
+    fn xml_tag_attributes(&self, with_xmlns: bool) -> Option<String> {
+        let xml = write_xml_string(|writer| self.write_xml_tag_attributes(writer, with_xmlns));
 
-        if header {
-            xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n");
-        }
+        return (!xml.is_empty()).then_some(xml);
+    }
 
-        xml.push_str(&self.xml_tag_start(with_xmlns));
+    #[inline]
+    fn xml_inner(&self, with_xmlns: bool) -> Option<String> {
+        let xml = write_xml_string(|writer| self.write_xml_inner(writer, with_xmlns));
 
-        if let Some(xml_inner) = self.xml_inner(with_xmlns) {
-            xml.push_str(&xml_inner);
-        }
+        return (!xml.is_empty()).then_some(xml);
+    }
 
-        xml.push_str(&self.xml_tag_end(with_xmlns));
+    #[inline]
+    fn xml_tag_start(&self, with_xmlns: bool) -> String {
+        write_xml_string(|writer| self.write_xml_tag_start(writer, with_xmlns))
+    }
 
-        return xml;
+    #[inline]
+    fn xml_tag_end(&self, with_xmlns: bool) -> String {
+        write_xml_string(|writer| self.write_xml_tag_end(writer, with_xmlns))
     }
 
     #[inline]
-    fn to_xml_bytes(&self, header: bool, with_xmlns: bool) -> Vec<u8> {
-        let mut xml = Vec::with_capacity(128);
+    fn to_xml_string(&self, header: bool, with_xmlns: bool) -> String {
+        xml_string_from_bytes(self.to_xml_bytes(header, with_xmlns))
+    }
 
-        if header {
-            xml.extend_from_slice(
-                b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n",
-            );
-        }
+    /// Serializes with the given options, e.g. pretty-printed.
+    #[inline]
+    fn to_xml_string_with_options(
+        &self,
+        header: bool,
+        with_xmlns: bool,
+        options: &SerializeOptions,
+    ) -> String {
+        xml_string_from_bytes(self.to_xml_bytes_with_options(header, with_xmlns, options))
+    }
 
-        xml.extend_from_slice(self.xml_tag_start(with_xmlns).as_bytes());
+    #[inline]
+    fn to_xml_bytes(&self, header: bool, with_xmlns: bool) -> Vec<u8> {
+        self.to_xml_bytes_with_options(header, with_xmlns, &SerializeOptions::default())
+    }
 
-        if let Some(xml_inner) = self.xml_inner(with_xmlns) {
-            xml.extend_from_slice(xml_inner.as_bytes());
-        }
+    #[inline]
+    fn to_xml_bytes_with_options(
+        &self,
+        header: bool,
+        with_xmlns: bool,
+        options: &SerializeOptions,
+    ) -> Vec<u8> {
+        let mut xml = Vec::with_capacity(128);
 
-        xml.extend_from_slice(self.xml_tag_end(with_xmlns).as_bytes());
+        // Writing into a `Vec` does not fail.
+        let _ = self.write_xml_with_options(&mut xml, header, with_xmlns, options);
 
         return xml;
     }
@@ -232,6 +1824,33 @@ pub fn resolve_zip_file_path(path: &str) -> String {
     stack.join("/")
 }
 
+/// Splits a part name into its directory (including the trailing `/`) and file name.
+#[inline]
+pub fn split_part_path(path: &str) -> (&str, &str) {
+    match path.rfind('/') {
+        Some(index) => path.split_at(index + 1),
+        None => ("", path),
+    }
+}
+
+/// Returns the `_rels/*.rels` part name holding the relationships of the given part.
+#[inline]
+pub fn resolve_rels_path(path: &str) -> String {
+    let (dir_path, file_name) = split_part_path(path);
+
+    resolve_zip_file_path(&format!("{dir_path}_rels/{file_name}.rels"))
+}
+
+/// Resolves a relationship target against the directory of its source part.
+#[inline]
+pub fn resolve_relationship_target(dir_path: &str, target: &str) -> String {
+    if target.starts_with('/') {
+        resolve_zip_file_path(target)
+    } else {
+        resolve_zip_file_path(&format!("{dir_path}{target}"))
+    }
+}
+
 #[inline]
 pub fn parse_bool_bytes(b: &[u8]) -> Result<bool, SdkErrorReport> {
     match b {
@@ -245,25 +1864,61 @@ pub fn parse_bool_bytes(b: &[u8]) -> Result<bool, SdkErrorReport> {
     }
 }
 
+/// Appends the text a general entity or character reference stands for.
+pub fn push_general_ref(text: &mut String, r: &BytesRef) -> Result<(), SdkErrorReport> {
+    if let Some(ch) = r.resolve_char_ref().map_err(SdkError::from)? {
+        text.push(ch);
+
+        return Ok(());
+    }
+
+    let name = r.decode().map_err(SdkError::from)?;
+
+    let Some(value) = quick_xml::escape::resolve_predefined_entity(&name) else {
+        return Err(SdkError::CommonError(format!("&{name};")).into());
+    };
+
+    text.push_str(value);
+
+    Ok(())
+}
+
+/// Appends text borrowed from the input, only allocating for text split across several events.
+#[cfg(feature = "borrowed")]
 #[inline]
-pub fn as_xml_attribute(key: &str, value: &str) -> String {
-    let mut attribute = String::with_capacity(16);
+pub fn push_borrowed_text<'de>(
+    text: &mut Option<std::borrow::Cow<'de, str>>,
+    value: std::borrow::Cow<'de, str>,
+) {
+    match text {
+        Some(text) => text.to_mut().push_str(&value),
+        None => *text = Some(value),
+    }
+}
 
-    attribute.push(' ');
-    attribute.push_str(key);
-    attribute.push_str("=\"");
-    attribute.push_str(value);
-    attribute.push('"');
+/// Returns bytes read by a [`SliceReader`], such as a qualified name, as a string borrowed from
+/// the input.
+#[cfg(feature = "borrowed")]
+#[inline]
+pub fn borrowed_str(bytes: &[u8]) -> Result<&str, SdkErrorReport> {
+    Ok(std::str::from_utf8(bytes).map_err(|e| SdkError::CommonError(e.to_string()))?)
+}
 
-    return attribute;
+/// Whether text loses meaning without `xml:space="preserve"`, i.e. has leading or trailing
+/// whitespace, or whitespace other than single spaces.
+pub fn needs_space_preserve(text: &str) -> bool {
+    return text.starts_with(char::is_whitespace)
+        || text.ends_with(char::is_whitespace)
+        || text.contains("  ")
+        || text.contains(['\t', '\n', '\r']);
 }
 
 #[inline(always)]
 pub(crate) fn expect_event_start<'de>(
     xml_reader: &mut impl XmlReader<'de>,
     xml_event: Option<(BytesStart<'de>, bool)>,
-    tag_prefixed: &[u8],
-    tag: &[u8],
+    namespace_uri: &[u8],
+    local_name: &[u8],
 ) -> Result<(BytesStart<'de>, bool), SdkErrorReport> {
     debug!("xml_event: {:?}", xml_event);
 
@@ -288,20 +1943,24 @@ pub(crate) fn expect_event_start<'de>(
 
     debug!("({event:?}, {empty_tag})");
 
-    let event_name = event.name().0;
-    if !(event_name == tag_prefixed || event_name == tag) {
-        let expected_tag_prefixed = String::from_utf8_lossy(tag_prefixed).to_string();
-        let expected_tag = String::from_utf8_lossy(tag).to_string();
-        let found_event_name = String::from_utf8_lossy(event_name).to_string();
+    let (event_namespace_uri, event_local_name) = xml_reader.resolve_element(event.name());
 
-        warn!(
-            "Mismatch: [{found_event_name}] does not match [{expected_tag_prefixed}] OR [{expected_tag}]"
+    if event_local_name != local_name || event_namespace_uri.is_some_and(|uri| uri != namespace_uri)
+    {
+        let expected = format!(
+            "{{{}}}{}",
+            String::from_utf8_lossy(namespace_uri),
+            String::from_utf8_lossy(local_name)
+        );
+        let found = format!(
+            "{{{}}}{}",
+            String::from_utf8_lossy(event_namespace_uri.unwrap_or_default()),
+            String::from_utf8_lossy(event_local_name)
         );
 
-        Err(SdkError::MismatchError {
-            expected: format!("{expected_tag_prefixed} OR {expected_tag}"),
-            found: found_event_name,
-        })?;
+        warn!("Mismatch: [{found}] does not match [{expected}]");
+
+        Err(SdkError::MismatchError { expected, found })?;
     }
 
     Ok((event, empty_tag))
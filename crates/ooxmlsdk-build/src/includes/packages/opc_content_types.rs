@@ -1,11 +1,13 @@
 use super::super::common::*;
 use quick_xml::events::BytesStart;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+const NAMESPACE_URI: &[u8] = b"http://schemas.openxmlformats.org/package/2006/content-types";
 
 #[derive(Clone, Debug, Default)]
 pub struct Types {
     pub xmlns: Option<String>,
-    pub xmlns_map: HashMap<String, String>,
+    pub xmlns_map: BTreeMap<String, String>,
     pub mc_ignorable: Option<String>,
     pub children: Vec<TypesChildChoice>,
 }
@@ -18,15 +20,137 @@ pub enum TypesChildChoice {
     None,
 }
 
+impl Types {
+    pub const XMLNS: &str = "http://schemas.openxmlformats.org/package/2006/content-types";
+
+    /// Creates content types with the `Default` entries every package needs.
+    pub fn new() -> Self {
+        Self {
+            xmlns: Some(Self::XMLNS.to_string()),
+            children: vec![
+                TypesChildChoice::Default(Box::new(Default {
+                    extension: "rels".to_string(),
+                    content_type: "application/vnd.openxmlformats-package.relationships+xml"
+                        .to_string(),
+                })),
+                TypesChildChoice::Default(Box::new(Default {
+                    extension: "xml".to_string(),
+                    content_type: "application/xml".to_string(),
+                })),
+            ],
+            ..std::default::Default::default()
+        }
+    }
+
+    /// Looks up the content type of a part, preferring an `Override` for the part name over a
+    /// `Default` for its extension. Both are matched ASCII case-insensitively.
+    pub fn get_content_type(&self, part_name: &str) -> Option<&str> {
+        let part_name = part_name.trim_start_matches('/');
+
+        let override_content_type = self.children.iter().find_map(|child| match child {
+            TypesChildChoice::Override(o)
+                if o.part_name
+                    .trim_start_matches('/')
+                    .eq_ignore_ascii_case(part_name) =>
+            {
+                Some(o.content_type.as_str())
+            }
+            _ => None,
+        });
+
+        if override_content_type.is_some() {
+            return override_content_type;
+        }
+
+        let (_, extension) = part_name.rsplit_once('.')?;
+
+        return self.children.iter().find_map(|child| match child {
+            TypesChildChoice::Default(d) if d.extension.eq_ignore_ascii_case(extension) => {
+                Some(d.content_type.as_str())
+            }
+            _ => None,
+        });
+    }
+
+    pub fn contains_override(&self, part_name: &str) -> bool {
+        let part_name = part_name.trim_start_matches('/');
+
+        return self.children.iter().any(|child| match child {
+            TypesChildChoice::Override(o) => o
+                .part_name
+                .trim_start_matches('/')
+                .eq_ignore_ascii_case(part_name),
+            _ => false,
+        });
+    }
+
+    /// Registers the content type of a part, replacing any previous `Override` for it.
+    pub fn add_override(&mut self, part_name: &str, content_type: &str) {
+        self.remove_override(part_name);
+
+        self.children
+            .push(TypesChildChoice::Override(Box::new(Override {
+                content_type: content_type.to_string(),
+                part_name: format!("/{}", part_name.trim_start_matches('/')),
+            })));
+    }
+
+    pub fn remove_override(&mut self, part_name: &str) {
+        let part_name = part_name.trim_start_matches('/');
+
+        self.children.retain(|child| match child {
+            TypesChildChoice::Override(o) => !o
+                .part_name
+                .trim_start_matches('/')
+                .eq_ignore_ascii_case(part_name),
+            _ => true,
+        });
+    }
+
+    /// Finds a part name `{dir_path}{target}{N}{extension}` that has no `Override` yet and is not
+    /// rejected by `is_taken`. Unless `numbered`, the plain `{dir_path}{target}{extension}` is
+    /// tried first.
+    pub fn next_part_name(
+        &self,
+        dir_path: &str,
+        target: &str,
+        extension: &str,
+        numbered: bool,
+        is_taken: impl Fn(&str) -> bool,
+    ) -> String {
+        let is_free = |part_name: &str| !self.contains_override(part_name) && !is_taken(part_name);
+
+        if !numbered {
+            let part_name = resolve_zip_file_path(&format!("{dir_path}{target}{extension}"));
+
+            if is_free(&part_name) {
+                return part_name;
+            }
+        }
+
+        let mut index = 1;
+
+        loop {
+            let part_name = resolve_zip_file_path(&format!("{dir_path}{target}{index}{extension}"));
+
+            if is_free(&part_name) {
+                return part_name;
+            }
+
+            index += 1;
+        }
+    }
+}
+
 impl Deserializeable for Types {
     fn deserialize_inner<'de>(
         xml_reader: &mut impl XmlReader<'de>,
         xml_event: Option<(BytesStart<'de>, bool)>,
     ) -> Result<Self, SdkErrorReport> {
-        let (e, empty_tag) = expect_event_start(xml_reader, xml_event, b"w:Types", b"Types")?;
+        let (e, empty_tag) = expect_event_start(xml_reader, xml_event, NAMESPACE_URI, b"Types")?;
 
         let mut xmlns = None;
-        let mut xmlns_map = HashMap::<String, String>::new();
+        let mut xmlns_map = BTreeMap::<String, String>::new();
         let mut mc_ignorable = None;
 
         let mut children = vec![];
@@ -75,24 +199,26 @@ impl Deserializeable for Types {
                         e_empty = true;
                         e_opt = Some(e);
                     }
-                    quick_xml::events::Event::End(e) => match e.name().as_ref() {
-                        b"w:Types" | b"Types" => {
-                            break;
-                        }
-                        _ => (),
-                    },
+                    quick_xml::events::Event::End(e)
+                        if matches!(
+                            xml_reader.resolve_element(e.name()),
+                            (Some(NAMESPACE_URI) | None, b"Types")
+                        ) =>
+                    {
+                        break;
+                    }
                     quick_xml::events::Event::Eof => Err(SdkError::UnknownError)?,
                     _ => (),
                 }
 
                 if let Some(e) = e_opt {
-                    match e.name().as_ref() {
-                        b"w:Default" | b"Default" => {
+                    match xml_reader.resolve_element(e.name()) {
+                        (Some(NAMESPACE_URI) | None, b"Default") => {
                             children.push(TypesChildChoice::Default(std::boxed::Box::new(
                                 Default::deserialize_inner(xml_reader, Some((e, e_empty)))?,
                             )));
                         }
-                        b"w:Override" | b"Override" => {
+                        (Some(NAMESPACE_URI) | None, b"Override") => {
                             children.push(TypesChildChoice::Override(std::boxed::Box::new(
                                 Override::deserialize_inner(xml_reader, Some((e, e_empty)))?,
                             )));
@@ -115,44 +241,42 @@ impl Deserializeable for Types {
 impl Serializeable for Types {
     const PREFIXED_NAME: &str = "Types";
 
-    const NAME: &str = "w:Types";
-
-    fn xml_tag_attributes(&self, with_xmlns: bool) -> Option<String> {
-        let mut attributes = String::with_capacity(
-            const { "xmlns".len() + "xmlns:".len() + "mc:Ignorable".len() + 32 },
-        );
+    const NAME: &str = "Types";
 
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
         if with_xmlns && let Some(xmlns) = &self.xmlns {
-            attributes.push_str(&as_xml_attribute("xmlns", xmlns));
+            writer.write_attribute("xmlns", xmlns)?;
         }
 
         for (key, value) in &self.xmlns_map {
-            attributes.push_str(&as_xml_attribute(&format!("xmlns:{key}"), value));
+            writer.write_xmlns_attribute(key, value)?;
         }
 
         if let Some(mc_ignorable) = &self.mc_ignorable {
-            attributes.push_str(&as_xml_attribute("mc:Ignorable", mc_ignorable));
+            writer.write_attribute("mc:Ignorable", mc_ignorable)?;
         }
 
-        return Some(attributes);
+        Ok(())
     }
 
-    fn xml_inner(&self, with_xmlns: bool) -> Option<String> {
-        let mut xml = String::with_capacity(32);
-
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
         for child in &self.children {
             match child {
-                TypesChildChoice::Default(child) => {
-                    xml.push_str(&child.to_xml_string(false, with_xmlns))
-                }
-                TypesChildChoice::Override(child) => {
-                    xml.push_str(&child.to_xml_string(false, with_xmlns))
-                }
+                TypesChildChoice::Default(child) => child.write_xml_element(writer, with_xmlns)?,
+                TypesChildChoice::Override(child) => child.write_xml_element(writer, with_xmlns)?,
                 TypesChildChoice::None => (),
             }
         }
 
-        return Some(xml);
+        Ok(())
     }
 }
 
@@ -167,7 +291,7 @@ impl Deserializeable for Default {
         xml_reader: &mut impl XmlReader<'de>,
         xml_event: Option<(BytesStart<'de>, bool)>,
     ) -> Result<Self, SdkErrorReport> {
-        let (e, _) = expect_event_start(xml_reader, xml_event, b"w:Default", b"Default")?;
+        let (e, _) = expect_event_start(xml_reader, xml_event, NAMESPACE_URI, b"Default")?;
 
         let mut extension = None;
         let mut content_type = None;
@@ -207,21 +331,28 @@ impl Deserializeable for Default {
 }
 
 impl Serializeable for Default {
-    const PREFIXED_NAME: &str = "w:Default";
+    const PREFIXED_NAME: &str = "Default";
 
     const NAME: &str = "Default";
 
-    fn xml_tag_attributes(&self, _with_xmlns: bool) -> Option<String> {
-        let mut attributes =
-            String::with_capacity(const { "Extension".len() + "ContentType".len() + 32 });
-
-        attributes.push_str(&as_xml_attribute("Extension", &self.extension));
-        attributes.push_str(&as_xml_attribute("ContentType", &self.content_type));
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        writer.write_attribute("Extension", &self.extension)?;
+        writer.write_attribute("ContentType", &self.content_type)?;
 
-        return Some(attributes);
+        Ok(())
     }
 
-    fn xml_inner(&self, _with_xmlns: bool) -> Option<String> { None }
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        _writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -235,7 +366,7 @@ impl Deserializeable for Override {
         xml_reader: &mut impl XmlReader<'de>,
         xml_event: Option<(BytesStart<'de>, bool)>,
     ) -> Result<Self, SdkErrorReport> {
-        let (e, _) = expect_event_start(xml_reader, xml_event, b"w:Override", b"Override")?;
+        let (e, _) = expect_event_start(xml_reader, xml_event, NAMESPACE_URI, b"Override")?;
 
         let mut content_type = None;
         let mut part_name = None;
@@ -275,19 +406,26 @@ impl Deserializeable for Override {
 }
 
 impl Serializeable for Override {
-    const PREFIXED_NAME: &str = "w:Override";
+    const PREFIXED_NAME: &str = "Override";
 
     const NAME: &str = "Override";
 
-    fn xml_tag_attributes(&self, _with_xmlns: bool) -> Option<String> {
-        let mut attributes =
-            String::with_capacity(const { "Extension".len() + "PartName".len() + 32 });
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        writer.write_attribute("ContentType", &self.content_type)?;
+        writer.write_attribute("PartName", &self.part_name)?;
 
-        attributes.push_str(&as_xml_attribute("ContentType", &self.content_type));
-        attributes.push_str(&as_xml_attribute("PartName", &self.content_type));
-
-        return Some(attributes);
+        Ok(())
     }
 
-    fn xml_inner(&self, _with_xmlns: bool) -> Option<String> { None }
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        _writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
 }
@@ -1,10 +1,17 @@
 use super::super::common::*;
 use quick_xml::events::BytesStart;
 
+const CP_NAMESPACE_URI: &[u8] =
+    b"http://schemas.openxmlformats.org/package/2006/metadata/core-properties";
+
+const DC_NAMESPACE_URI: &[u8] = b"http://purl.org/dc/elements/1.1/";
+
+const DCTERMS_NAMESPACE_URI: &[u8] = b"http://purl.org/dc/terms/";
+
 #[derive(Clone, Debug, Default)]
 pub struct CoreProperties {
     pub xmlns: Option<String>,
-    pub xmlns_map: std::collections::HashMap<String, String>,
+    pub xmlns_map: std::collections::BTreeMap<String, String>,
     pub mc_ignorable: Option<String>,
     pub category: Option<String>,
     pub content_status: Option<String>,
@@ -28,16 +35,12 @@ impl Deserializeable for CoreProperties {
         xml_reader: &mut impl XmlReader<'de>,
         xml_event: Option<(BytesStart<'de>, bool)>,
     ) -> Result<Self, SdkErrorReport> {
-        let (e, empty_tag) = expect_event_start(
-            xml_reader,
-            xml_event,
-            b"cp:coreProperties",
-            b"coreProperties",
-        )?;
+        let (e, empty_tag) =
+            expect_event_start(xml_reader, xml_event, CP_NAMESPACE_URI, b"coreProperties")?;
 
         let mut xmlns = None;
 
-        let mut xmlns_map = std::collections::HashMap::<String, String>::new();
+        let mut xmlns_map = std::collections::BTreeMap::<String, String>::new();
 
         let mut mc_ignorable = None;
 
@@ -105,15 +108,15 @@ impl Deserializeable for CoreProperties {
             loop {
                 match xml_reader.next()? {
                     quick_xml::events::Event::Start(e) | quick_xml::events::Event::Empty(e) => {
-                        match e.name().as_ref() {
-                            b"cp:category" => {
+                        match xml_reader.resolve_element(e.name()) {
+                            (Some(CP_NAMESPACE_URI), b"category") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     category = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"cp:contentStatus" => {
+                            (Some(CP_NAMESPACE_URI), b"contentStatus") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     content_status =
                                         Some(t.decode().map_err(SdkError::from)?.to_string())
@@ -121,21 +124,21 @@ impl Deserializeable for CoreProperties {
 
                                 xml_reader.next()?;
                             }
-                            b"dcterms:created" => {
+                            (Some(DCTERMS_NAMESPACE_URI), b"created") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     created = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"dc:creator" => {
+                            (Some(DC_NAMESPACE_URI), b"creator") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     creator = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"dc:description" => {
+                            (Some(DC_NAMESPACE_URI), b"description") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     description =
                                         Some(t.decode().map_err(SdkError::from)?.to_string())
@@ -143,7 +146,7 @@ impl Deserializeable for CoreProperties {
 
                                 xml_reader.next()?;
                             }
-                            b"dc:identifier" => {
+                            (Some(DC_NAMESPACE_URI), b"identifier") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     identifier =
                                         Some(t.decode().map_err(SdkError::from)?.to_string())
@@ -151,21 +154,21 @@ impl Deserializeable for CoreProperties {
 
                                 xml_reader.next()?;
                             }
-                            b"cp:keywords" => {
+                            (Some(CP_NAMESPACE_URI), b"keywords") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     keywords = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"dc:language" => {
+                            (Some(DC_NAMESPACE_URI), b"language") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     language = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"cp:lastModifiedBy" => {
+                            (Some(CP_NAMESPACE_URI), b"lastModifiedBy") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     last_modified_by =
                                         Some(t.decode().map_err(SdkError::from)?.to_string())
@@ -173,7 +176,7 @@ impl Deserializeable for CoreProperties {
 
                                 xml_reader.next()?;
                             }
-                            b"cp:lastPrinted" => {
+                            (Some(CP_NAMESPACE_URI), b"lastPrinted") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     last_printed =
                                         Some(t.decode().map_err(SdkError::from)?.to_string())
@@ -181,35 +184,35 @@ impl Deserializeable for CoreProperties {
 
                                 xml_reader.next()?;
                             }
-                            b"dcterms:modified" => {
+                            (Some(DCTERMS_NAMESPACE_URI), b"modified") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     modified = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"cp:revision" => {
+                            (Some(CP_NAMESPACE_URI), b"revision") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     revision = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"dc:subject" => {
+                            (Some(DC_NAMESPACE_URI), b"subject") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     subject = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"dc:title" => {
+                            (Some(DC_NAMESPACE_URI), b"title") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     title = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
 
                                 xml_reader.next()?;
                             }
-                            b"cp:version" => {
+                            (Some(CP_NAMESPACE_URI), b"version") => {
                                 if let quick_xml::events::Event::Text(t) = xml_reader.next()? {
                                     version = Some(t.decode().map_err(SdkError::from)?.to_string())
                                 }
@@ -219,12 +222,14 @@ impl Deserializeable for CoreProperties {
                             _ => Err(SdkError::CommonError("coreProperties".to_string()))?,
                         }
                     }
-                    quick_xml::events::Event::End(e) => match e.name().as_ref() {
-                        b"cp:coreProperties" | b"coreProperties" => {
-                            break;
-                        }
-                        _ => (),
-                    },
+                    quick_xml::events::Event::End(e)
+                        if matches!(
+                            xml_reader.resolve_element(e.name()),
+                            (Some(CP_NAMESPACE_URI) | None, b"coreProperties")
+                        ) =>
+                    {
+                        break;
+                    }
                     quick_xml::events::Event::Eof => Err(SdkError::UnknownError)?,
                     _ => (),
                 }
@@ -259,119 +264,123 @@ impl Serializeable for CoreProperties {
 
     const NAME: &str = "coreProperties";
 
-    fn xml_tag_attributes(&self, with_xmlns: bool) -> Option<String> {
-        let mut attributes = String::with_capacity(
-            const { "xmlns".len() + "xmlns:".len() + "mc:Ignorable".len() + 32 },
-        );
-
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
         if with_xmlns && let Some(xmlns) = &self.xmlns {
-            attributes.push_str(&as_xml_attribute("xmlns", xmlns));
+            writer.write_attribute("xmlns", xmlns)?;
         }
 
         for (key, value) in &self.xmlns_map {
-            attributes.push_str(&as_xml_attribute(&format!("xmlns:{key}"), value));
+            writer.write_xmlns_attribute(key, value)?;
         }
 
         if let Some(mc_ignorable) = &self.mc_ignorable {
-            attributes.push_str(&as_xml_attribute("mc:Ignorable", mc_ignorable));
+            writer.write_attribute("mc:Ignorable", mc_ignorable)?;
         }
 
-        return Some(attributes);
+        Ok(())
     }
 
-    fn xml_inner(&self, _with_xmlns: bool) -> Option<String> {
-        let mut xml = String::with_capacity(512);
-
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
         if let Some(category) = &self.category {
-            xml.push_str("<cp:category>");
-            xml.push_str(&quick_xml::escape::escape(category));
-            xml.push_str("</cp:category>");
+            writer.write_start_tag("cp:category")?;
+            writer.write_text(category)?;
+            writer.write_end_tag("cp:category")?;
         }
 
         if let Some(content_status) = &self.content_status {
-            xml.push_str("<cp:contentStatus>");
-            xml.push_str(&quick_xml::escape::escape(content_status));
-            xml.push_str("</cp:contentStatus>");
+            writer.write_start_tag("cp:contentStatus")?;
+            writer.write_text(content_status)?;
+            writer.write_end_tag("cp:contentStatus")?;
         }
 
         if let Some(created) = &self.created {
-            xml.push_str(r#"<dcterms:created xsi:type="dcterms:W3CDTF">"#);
-            xml.push_str(&quick_xml::escape::escape(created));
-            xml.push_str("</dcterms:created>");
+            writer.write_start_tag("dcterms:created")?;
+            writer.write_attribute("xsi:type", "dcterms:W3CDTF")?;
+            writer.write_text(created)?;
+            writer.write_end_tag("dcterms:created")?;
         }
 
         if let Some(creator) = &self.creator {
-            xml.push_str("<dc:creator>");
-            xml.push_str(&quick_xml::escape::escape(creator));
-            xml.push_str("</dc:creator>");
+            writer.write_start_tag("dc:creator")?;
+            writer.write_text(creator)?;
+            writer.write_end_tag("dc:creator")?;
         }
 
         if let Some(description) = &self.description {
-            xml.push_str("<dc:description>");
-            xml.push_str(&quick_xml::escape::escape(description));
-            xml.push_str("</dc:description>");
+            writer.write_start_tag("dc:description")?;
+            writer.write_text(description)?;
+            writer.write_end_tag("dc:description")?;
         }
 
         if let Some(identifier) = &self.identifier {
-            xml.push_str("<dc:identifier>");
-            xml.push_str(&quick_xml::escape::escape(identifier));
-            xml.push_str("</dc:identifier>");
+            writer.write_start_tag("dc:identifier")?;
+            writer.write_text(identifier)?;
+            writer.write_end_tag("dc:identifier")?;
         }
 
         if let Some(keywords) = &self.keywords {
-            xml.push_str("<cp:keywords>");
-            xml.push_str(&quick_xml::escape::escape(keywords));
-            xml.push_str("</cp:keywords>");
+            writer.write_start_tag("cp:keywords")?;
+            writer.write_text(keywords)?;
+            writer.write_end_tag("cp:keywords")?;
         }
 
         if let Some(language) = &self.language {
-            xml.push_str("<dc:language>");
-            xml.push_str(&quick_xml::escape::escape(language));
-            xml.push_str("</dc:language>");
+            writer.write_start_tag("dc:language")?;
+            writer.write_text(language)?;
+            writer.write_end_tag("dc:language")?;
         }
 
         if let Some(last_modified_by) = &self.last_modified_by {
-            xml.push_str("<cp:lastModifiedBy>");
-            xml.push_str(&quick_xml::escape::escape(last_modified_by));
-            xml.push_str("</cp:lastModifiedBy>");
+            writer.write_start_tag("cp:lastModifiedBy")?;
+            writer.write_text(last_modified_by)?;
+            writer.write_end_tag("cp:lastModifiedBy")?;
         }
 
         if let Some(last_printed) = &self.last_printed {
-            xml.push_str("<cp:lastPrinted>");
-            xml.push_str(&quick_xml::escape::escape(last_printed));
-            xml.push_str("</cp:lastPrinted>");
+            writer.write_start_tag("cp:lastPrinted")?;
+            writer.write_text(last_printed)?;
+            writer.write_end_tag("cp:lastPrinted")?;
         }
 
         if let Some(modified) = &self.modified {
-            xml.push_str(r#"<dcterms:modified xsi:type="dcterms:W3CDTF">"#);
-            xml.push_str(&quick_xml::escape::escape(modified));
-            xml.push_str("</dcterms:modified>");
+            writer.write_start_tag("dcterms:modified")?;
+            writer.write_attribute("xsi:type", "dcterms:W3CDTF")?;
+            writer.write_text(modified)?;
+            writer.write_end_tag("dcterms:modified")?;
         }
 
         if let Some(revision) = &self.revision {
-            xml.push_str("<cp:revision>");
-            xml.push_str(&quick_xml::escape::escape(revision));
-            xml.push_str("</cp:revision>");
+            writer.write_start_tag("cp:revision")?;
+            writer.write_text(revision)?;
+            writer.write_end_tag("cp:revision")?;
         }
 
         if let Some(subject) = &self.subject {
-            xml.push_str("<dc:subject>");
-            xml.push_str(&quick_xml::escape::escape(subject));
-            xml.push_str("</dc:subject>");
+            writer.write_start_tag("dc:subject")?;
+            writer.write_text(subject)?;
+            writer.write_end_tag("dc:subject")?;
         }
 
         if let Some(title) = &self.title {
-            xml.push_str("<dc:title>");
-            xml.push_str(&quick_xml::escape::escape(title));
-            xml.push_str("</dc:title>");
+            writer.write_start_tag("dc:title")?;
+            writer.write_text(title)?;
+            writer.write_end_tag("dc:title")?;
         }
 
         if let Some(version) = &self.version {
-            xml.push_str("<cp:version>");
-            xml.push_str(&quick_xml::escape::escape(version));
-            xml.push_str("</cp:version>");
+            writer.write_start_tag("cp:version")?;
+            writer.write_text(version)?;
+            writer.write_end_tag("cp:version")?;
         }
 
-        return Some(xml);
+        Ok(())
     }
 }
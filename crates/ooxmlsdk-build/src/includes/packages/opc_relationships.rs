@@ -2,25 +2,91 @@ use quick_xml::events::BytesStart;
 
 use super::super::common::*;
 
+const NAMESPACE_URI: &[u8] = b"http://schemas.openxmlformats.org/package/2006/relationships";
+
 #[derive(Clone, Debug, Default)]
 pub struct Relationships {
     pub xmlns: Option<String>,
-    pub xmlns_map: std::collections::HashMap<String, String>,
+    pub xmlns_map: std::collections::BTreeMap<String, String>,
     pub mc_ignorable: Option<String>,
     pub relationship: Vec<Relationship>,
 }
 
+impl Relationships {
+    pub const XMLNS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+
+    pub fn new() -> Self {
+        Self {
+            xmlns: Some(Self::XMLNS.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn get_relationship(&self, id: &str) -> Option<&Relationship> {
+        return self.relationship.iter().find(|r| r.id == id);
+    }
+
+    pub fn external_relationships(&self) -> impl Iterator<Item = &Relationship> {
+        return self.relationship.iter().filter(|r| r.is_external());
+    }
+
+    /// Returns the first `rIdN` not taken by any relationship of this part.
+    pub fn next_relationship_id(&self) -> String {
+        let mut index = self.relationship.len() + 1;
+
+        loop {
+            let id = format!("rId{index}");
+
+            if self.get_relationship(&id).is_none() {
+                return id;
+            }
+
+            index += 1;
+        }
+    }
+
+    /// Adds a relationship and returns its newly assigned id.
+    pub fn add_relationship(
+        &mut self,
+        r#type: &str,
+        target: &str,
+        target_mode: Option<TargetMode>,
+    ) -> String {
+        let id = self.next_relationship_id();
+
+        self.relationship.push(Relationship {
+            target_mode,
+            target: target.to_string(),
+            r#type: r#type.to_string(),
+            id: id.clone(),
+        });
+
+        return id;
+    }
+
+    /// Adds an external relationship and returns its newly assigned id.
+    pub fn add_external_relationship(&mut self, r#type: &str, target: &str) -> String {
+        return self.add_relationship(r#type, target, Some(TargetMode::External));
+    }
+
+    pub fn remove_relationship(&mut self, id: &str) -> Option<Relationship> {
+        let index = self.relationship.iter().position(|r| r.id == id)?;
+
+        return Some(self.relationship.remove(index));
+    }
+}
+
 impl Deserializeable for Relationships {
     fn deserialize_inner<'de>(
         xml_reader: &mut impl XmlReader<'de>,
         xml_event: Option<(BytesStart<'de>, bool)>,
     ) -> Result<Self, SdkErrorReport> {
         let (e, empty_tag) =
-            expect_event_start(xml_reader, xml_event, b"w:Relationships", b"Relationships")?;
+            expect_event_start(xml_reader, xml_event, NAMESPACE_URI, b"Relationships")?;
 
         let mut xmlns = None;
 
-        let mut xmlns_map = std::collections::HashMap::<String, String>::new();
+        let mut xmlns_map = std::collections::BTreeMap::<String, String>::new();
 
         let mut mc_ignorable = None;
 
@@ -69,19 +135,21 @@ impl Deserializeable for Relationships {
                         e_empty = true;
                         e_opt = Some(e);
                     }
-                    quick_xml::events::Event::End(e) => match e.name().as_ref() {
-                        b"w:Relationships" | b"Relationships" => {
-                            break;
-                        }
-                        _ => (),
-                    },
+                    quick_xml::events::Event::End(e)
+                        if matches!(
+                            xml_reader.resolve_element(e.name()),
+                            (Some(NAMESPACE_URI) | None, b"Relationships")
+                        ) =>
+                    {
+                        break;
+                    }
                     quick_xml::events::Event::Eof => Err(SdkError::UnknownError)?,
                     _ => (),
                 }
 
                 if let Some(e) = e_opt {
-                    match e.name().as_ref() {
-                        b"w:Relationship" | b"Relationship" => {
+                    match xml_reader.resolve_element(e.name()) {
+                        (Some(NAMESPACE_URI) | None, b"Relationship") => {
                             relationship.push(Relationship::deserialize_inner(
                                 xml_reader,
                                 Some((e, e_empty)),
@@ -104,38 +172,40 @@ impl Deserializeable for Relationships {
 }
 
 impl Serializeable for Relationships {
-    const PREFIXED_NAME: &str = "w:Relationships";
+    const PREFIXED_NAME: &str = "Relationships";
 
     const NAME: &str = "Relationships";
 
-    fn xml_tag_attributes(&self, with_xmlns: bool) -> Option<String> {
-        let mut attributes = String::with_capacity(
-            const { "xmlns".len() + "xmlns:".len() + "mc:Ignorable".len() + 32 },
-        );
-
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
         if with_xmlns && let Some(xmlns) = &self.xmlns {
-            attributes.push_str(&as_xml_attribute("xmlns", xmlns));
+            writer.write_attribute("xmlns", xmlns)?;
         }
 
         for (key, value) in &self.xmlns_map {
-            attributes.push_str(&as_xml_attribute(&format!("xmlns:{key}"), value));
+            writer.write_xmlns_attribute(key, value)?;
         }
 
         if let Some(mc_ignorable) = &self.mc_ignorable {
-            attributes.push_str(&as_xml_attribute("mc:Ignorable", mc_ignorable));
+            writer.write_attribute("mc:Ignorable", mc_ignorable)?;
         }
 
-        return Some(attributes);
+        Ok(())
     }
 
-    fn xml_inner(&self, with_xmlns: bool) -> Option<String> {
-        let mut xml = String::with_capacity(32);
-
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        with_xmlns: bool,
+    ) -> std::io::Result<()> {
         for child in &self.relationship {
-            xml.push_str(&child.to_xml_string(false, with_xmlns));
+            child.write_xml_element(writer, with_xmlns)?;
         }
 
-        return Some(xml);
+        Ok(())
     }
 }
 
@@ -152,7 +222,7 @@ impl Deserializeable for Relationship {
         xml_reader: &mut impl XmlReader<'de>,
         xml_event: Option<(BytesStart<'de>, bool)>,
     ) -> Result<Self, SdkErrorReport> {
-        let (e, _) = expect_event_start(xml_reader, xml_event, b"w:Relationship", b"Relationship")?;
+        let (e, _) = expect_event_start(xml_reader, xml_event, NAMESPACE_URI, b"Relationship")?;
 
         let mut target_mode = None;
 
@@ -214,25 +284,51 @@ impl Deserializeable for Relationship {
 }
 
 impl Serializeable for Relationship {
-    const PREFIXED_NAME: &str = "w:Relationship";
+    const PREFIXED_NAME: &str = "Relationship";
 
     const NAME: &str = "Relationship";
 
-    fn xml_tag_attributes(&self, _with_xmlns: bool) -> Option<String> {
-        let mut attributes =
-            String::with_capacity(const { "Extension".len() + "ContentType".len() + 32 });
-
+    fn write_xml_tag_attributes<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
         if let Some(target_mode) = &self.target_mode {
-            attributes.push_str(&as_xml_attribute("TargetMode", &target_mode.to_string()));
+            writer.write_attribute("TargetMode", &target_mode.to_string())?;
         }
-        attributes.push_str(&as_xml_attribute("Target", &self.target));
-        attributes.push_str(&as_xml_attribute("Type", &self.r#type));
-        attributes.push_str(&as_xml_attribute("Id", &self.id));
 
-        return Some(attributes);
+        writer.write_attribute("Target", &self.target)?;
+        writer.write_attribute("Type", &self.r#type)?;
+        writer.write_attribute("Id", &self.id)?;
+
+        Ok(())
     }
 
-    fn xml_inner(&self, _with_xmlns: bool) -> Option<String> { None }
+    fn write_xml_inner<W: std::io::Write>(
+        &self,
+        _writer: &mut XmlWriter<W>,
+        _with_xmlns: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Relationship {
+    pub const HYPERLINK: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink";
+
+    pub const IMAGE: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image";
+
+    pub const ATTACHED_TEMPLATE: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/attachedTemplate";
+
+    pub const OLE_OBJECT: &str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/oleObject";
+
+    pub fn is_external(&self) -> bool {
+        return matches!(self.target_mode, Some(TargetMode::External));
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -0,0 +1,32 @@
+/// The kind of package, determined by the content type of its main part, e.g. a `.docx` document,
+/// a `.dotx` template or a `.xlsm` macro-enabled workbook.
+///
+/// Not every package supports every document type: slideshows only exist for presentations, and
+/// add-ins only for spreadsheets and presentations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DocumentType {
+    #[default]
+    Document,
+    MacroEnabledDocument,
+    Template,
+    MacroEnabledTemplate,
+    Slideshow,
+    MacroEnabledSlideshow,
+    AddIn,
+}
+
+impl DocumentType {
+    pub fn is_macro_enabled(&self) -> bool {
+        return matches!(
+            self,
+            Self::MacroEnabledDocument
+                | Self::MacroEnabledTemplate
+                | Self::MacroEnabledSlideshow
+                | Self::AddIn
+        );
+    }
+
+    pub fn is_template(&self) -> bool {
+        return matches!(self, Self::Template | Self::MacroEnabledTemplate);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::common::{opc_content_types::Types, *};
+use quick_xml::{Reader, events::Event};
+use std::io::{Cursor, Read, Write};
+
+const PACKAGE_XMLNS: &str = "http://schemas.microsoft.com/office/2006/xmlPackage";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Converts a Flat OPC document (`pkg:package` holding `pkg:part` elements) into a zip package.
+pub(crate) fn flat_opc_to_zip<R: Read>(mut reader: R) -> Result<Vec<u8>, SdkErrorReport> {
+    let mut flat_opc = String::new();
+
+    reader
+        .read_to_string(&mut flat_opc)
+        .map_err(SdkError::from)?;
+
+    let mut xml_reader = Reader::from_str(&flat_opc);
+
+    let mut content_types = Types::new();
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    let mut part_name = String::new();
+
+    loop {
+        match xml_reader.read_event().map_err(SdkError::from)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"part" => {
+                let mut content_type = String::new();
+
+                part_name.clear();
+
+                for attr in e.attributes() {
+                    let attr = attr.map_err(SdkError::from)?;
+
+                    match attr.key.local_name().as_ref() {
+                        b"name" => {
+                            part_name = attr
+                                .unescape_value()
+                                .map_err(SdkError::from)?
+                                .trim_start_matches('/')
+                                .to_string();
+                        }
+                        b"contentType" => {
+                            content_type =
+                                attr.unescape_value().map_err(SdkError::from)?.into_owned();
+                        }
+                        _ => {}
+                    }
+                }
+
+                if part_name.is_empty() {
+                    return Err(SdkError::CommonError("pkg:name".to_string()).into());
+                }
+
+                if content_types.get_content_type(&part_name) != Some(content_type.as_str()) {
+                    content_types.add_override(&part_name, &content_type);
+                }
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"xmlData" => {
+                let xml_data = xml_reader.read_text(e.name()).map_err(SdkError::from)?;
+
+                zip.start_file(&part_name, options)
+                    .map_err(SdkError::from)?;
+
+                zip.write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n")
+                    .map_err(SdkError::from)?;
+
+                zip.write_all(xml_data.trim().as_bytes())
+                    .map_err(SdkError::from)?;
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"binaryData" => {
+                let binary_data = xml_reader.read_text(e.name()).map_err(SdkError::from)?;
+
+                zip.start_file(&part_name, options)
+                    .map_err(SdkError::from)?;
+
+                zip.write_all(&decode_base64(&binary_data)?)
+                    .map_err(SdkError::from)?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    zip.start_file("[Content_Types].xml", options)
+        .map_err(SdkError::from)?;
+
+    content_types
+        .write_xml(&mut zip, true, true)
+        .map_err(SdkError::from)?;
+
+    Ok(zip.finish().map_err(SdkError::from)?.into_inner())
+}
+
+/// Converts a zip package into a Flat OPC document, embedding XML parts as `pkg:xmlData` and all
+/// other parts as base64 `pkg:binaryData`.
+pub(crate) fn zip_to_flat_opc<R: Read + std::io::Seek, W: Write>(
+    reader: R,
+    mut writer: W,
+    progid: Option<&str>,
+) -> Result<(), SdkErrorReport> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(SdkError::from)?;
+
+    let content_types = Types::from_reader(std::io::BufReader::new(
+        archive
+            .by_name("[Content_Types].xml")
+            .map_err(SdkError::from)?,
+    ))?;
+
+    let mut flat_opc = String::from("<?xml version=\"1.0\" standalone=\"yes\"?>\n");
+
+    if let Some(progid) = progid {
+        flat_opc.push_str(&format!("<?mso-application progid=\"{progid}\"?>\n"));
+    }
+
+    flat_opc.push_str(&format!("<pkg:package xmlns:pkg=\"{PACKAGE_XMLNS}\">"));
+
+    for i in 0..archive.len() {
+        let mut zip_entry = archive.by_index(i).map_err(SdkError::from)?;
+
+        let part_name = zip_entry.name().to_string();
+
+        if zip_entry.is_dir() || part_name == "[Content_Types].xml" {
+            continue;
+        }
+
+        let content_type = content_types
+            .get_content_type(&part_name)
+            .unwrap_or("application/octet-stream");
+
+        let mut part_content = Vec::with_capacity(zip_entry.size() as usize);
+
+        zip_entry
+            .read_to_end(&mut part_content)
+            .map_err(SdkError::from)?;
+
+        flat_opc.push_str(&format!(
+            "<pkg:part pkg:name=\"/{}\" pkg:contentType=\"{}\">",
+            quick_xml::escape::escape(part_name.as_str()),
+            quick_xml::escape::escape(content_type),
+        ));
+
+        if content_type.ends_with("+xml") || content_type.ends_with("/xml") {
+            let xml_data = String::from_utf8(part_content)
+                .map_err(|e| SdkError::CommonError(e.to_string()))?;
+
+            flat_opc.push_str("<pkg:xmlData>");
+            flat_opc.push_str(strip_xml_declaration(&xml_data));
+            flat_opc.push_str("</pkg:xmlData>");
+        } else {
+            flat_opc.push_str("<pkg:binaryData>");
+            flat_opc.push_str(&encode_base64(&part_content));
+            flat_opc.push_str("</pkg:binaryData>");
+        }
+
+        flat_opc.push_str("</pkg:part>");
+    }
+
+    flat_opc.push_str("</pkg:package>");
+
+    writer
+        .write_all(flat_opc.as_bytes())
+        .map_err(SdkError::from)?;
+
+    Ok(())
+}
+
+fn strip_xml_declaration(xml: &str) -> &str {
+    let xml = xml.trim_start_matches('\u{feff}').trim_start();
+
+    if xml.starts_with("<?xml")
+        && let Some(end) = xml.find("?>")
+    {
+        return xml[end + 2..].trim_start();
+    }
+
+    return xml;
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(n >> (18 - i * 6)) as usize & 0x3F] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    return encoded;
+}
+
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, SdkErrorReport> {
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+
+    let mut n = 0u32;
+    let mut bits = 0;
+
+    for c in encoded.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return Err(SdkError::CommonError("pkg:binaryData".to_string()).into()),
+        };
+
+        n = n << 6 | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((n >> bits) as u8);
+        }
+    }
+
+    Ok(decoded)
+}
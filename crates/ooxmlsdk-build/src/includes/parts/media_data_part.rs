@@ -0,0 +1,55 @@
+use crate::{common::*, parts::package_reader::PackageReader};
+use std::collections::HashSet;
+
+/// A package-level data part holding the bytes of embedded media (audio, video or other media
+/// files), shared by the parts referencing it through a data part reference relationship.
+#[derive(Clone, Debug, Default)]
+pub struct MediaDataPart {
+    pub content_type: Option<String>,
+    pub inner_path: String,
+    pub part_content: Vec<u8>,
+}
+
+impl MediaDataPart {
+    pub(crate) fn new_from_package_reader(
+        path: &str,
+        package_reader: &mut PackageReader<'_>,
+    ) -> Result<Self, SdkErrorReport> {
+        Ok(Self {
+            content_type: package_reader.content_type(path),
+            inner_path: path.to_string(),
+            part_content: package_reader.read_entry(path)?,
+        })
+    }
+
+    pub(crate) fn save_zip<W: std::io::Write + std::io::Seek>(
+        &self,
+        zip: &mut zip::ZipWriter<W>,
+        entry_set: &mut HashSet<String>,
+        save_options: &SaveOptions,
+    ) -> Result<(), SdkErrorReport> {
+        use std::io::Write;
+
+        let options = save_options.zip_file_options();
+
+        let directory_path = resolve_zip_file_path(split_part_path(&self.inner_path).0);
+
+        if !directory_path.is_empty() && !entry_set.contains(&directory_path) {
+            zip.add_directory(&directory_path, options)
+                .map_err(SdkError::from)?;
+
+            entry_set.insert(directory_path);
+        }
+
+        if !entry_set.contains(&self.inner_path) {
+            zip.start_file(&self.inner_path, options)
+                .map_err(SdkError::from)?;
+
+            zip.write_all(&self.part_content).map_err(SdkError::from)?;
+
+            entry_set.insert(self.inner_path.to_string());
+        }
+
+        Ok(())
+    }
+}
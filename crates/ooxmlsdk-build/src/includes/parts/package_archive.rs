@@ -0,0 +1,57 @@
+use crate::common::*;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+pub trait ReadSeek: std::io::Read + std::io::Seek + Send {}
+
+impl<T: std::io::Read + std::io::Seek + Send> ReadSeek for T {}
+
+/// A zip archive kept open by lazily loaded packages, shared by all parts not parsed yet.
+#[derive(Clone)]
+pub struct PackageArchive(Arc<Mutex<zip::ZipArchive<Box<dyn ReadSeek>>>>);
+
+impl PackageArchive {
+    pub fn new<R: std::io::Read + std::io::Seek + Send + 'static>(
+        reader: R,
+    ) -> Result<Self, SdkErrorReport> {
+        let reader: Box<dyn ReadSeek> = Box::new(reader);
+
+        let archive = zip::ZipArchive::new(reader).map_err(SdkError::from)?;
+
+        Ok(Self(Arc::new(Mutex::new(archive))))
+    }
+
+    pub fn lock(
+        &self,
+    ) -> Result<MutexGuard<'_, zip::ZipArchive<Box<dyn ReadSeek>>>, SdkErrorReport> {
+        Ok(self
+            .0
+            .lock()
+            .map_err(|e| SdkError::CommonError(e.to_string()))?)
+    }
+}
+
+impl std::fmt::Debug for PackageArchive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PackageArchive").finish_non_exhaustive()
+    }
+}
+
+/// Collects the names of all file entries of an archive.
+pub(crate) fn archive_file_path_set<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<HashSet<String>, SdkErrorReport> {
+    let mut file_path_set = HashSet::with_capacity(archive.len());
+
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(SdkError::from)?;
+
+        if let Some(path) = file.enclosed_name() {
+            file_path_set.insert(path.to_string_lossy().into_owned());
+        }
+    }
+
+    Ok(file_path_set)
+}
@@ -0,0 +1,134 @@
+use crate::common::{opc_relationships::Relationships, *};
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+    path::Path,
+};
+
+/// The entries of a zip package, each decompressed once into memory, for read-only access to
+/// parts through their borrowed representation in `crate::borrowed`.
+///
+/// Root elements parsed by [`Self::root_element`] borrow their strings from the buffers instead of
+/// allocating them, so they live as long as the `PackageBuffers` they are read from.
+#[derive(Clone, Debug, Default)]
+pub struct PackageBuffers {
+    entries: HashMap<String, Vec<u8>>,
+}
+
+impl PackageBuffers {
+    pub fn new<R: Read + Seek>(reader: R) -> Result<Self, SdkErrorReport> {
+        let mut archive = zip::ZipArchive::new(reader).map_err(SdkError::from)?;
+
+        let mut entries = HashMap::with_capacity(archive.len());
+
+        for i in 0..archive.len() {
+            let mut zip_entry = archive.by_index(i).map_err(SdkError::from)?;
+
+            if zip_entry.is_dir() {
+                continue;
+            }
+
+            let Some(entry_path) = zip_entry.enclosed_name() else {
+                return Err(SdkError::CommonError(zip_entry.name().to_string()).into());
+            };
+
+            let entry_path = entry_path.to_string_lossy().into_owned();
+
+            let mut buffer = Vec::with_capacity(zip_entry.size() as usize);
+
+            zip_entry.read_to_end(&mut buffer).map_err(SdkError::from)?;
+
+            entries.insert(entry_path, buffer);
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn new_from_file<P: AsRef<Path>>(path: P) -> Result<Self, SdkErrorReport> {
+        Self::new(std::io::BufReader::new(
+            std::fs::File::open(path).map_err(SdkError::from)?,
+        ))
+    }
+
+    /// Returns the names of all entries, in no particular order.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        return self.entries.keys().map(String::as_str);
+    }
+
+    /// Returns the decompressed content of an entry.
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        return self
+            .entries
+            .get(resolve_zip_file_path(path).as_str())
+            .map(Vec::as_slice);
+    }
+
+    /// Returns the relationships of a part, or of the package itself for an empty path.
+    pub fn relationships(&self, path: &str) -> Result<Option<Relationships>, SdkErrorReport> {
+        let rels_path = if path.is_empty() {
+            "_rels/.rels".to_string()
+        } else {
+            resolve_rels_path(path)
+        };
+
+        let Some(buffer) = self.entries.get(&rels_path) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Relationships::from_reader(buffer.as_slice())?))
+    }
+
+    /// Returns the names of the internal parts targeted by the relationships of the given type of
+    /// a part, or of the package itself for an empty path.
+    pub fn related_part_paths(
+        &self,
+        path: &str,
+        relationship_type: &str,
+    ) -> Result<Vec<String>, SdkErrorReport> {
+        let Some(relationships) = self.relationships(path)? else {
+            return Ok(vec![]);
+        };
+
+        let (dir_path, _) = split_part_path(path);
+
+        return Ok(relationships
+            .relationship
+            .iter()
+            .filter(|relationship| relationship.r#type == relationship_type)
+            .filter(|relationship| !relationship.is_external())
+            .map(|relationship| resolve_relationship_target(dir_path, &relationship.target))
+            .collect());
+    }
+
+    /// Parses the root element of a part into its borrowed representation, e.g.
+    /// `root_element::<Document>("word/document.xml")`.
+    pub fn root_element<T: HasBorrowed>(
+        &self,
+        path: &str,
+    ) -> Result<T::Borrowed<'_>, SdkErrorReport> {
+        Ok(self
+            .root_element_with_options::<T>(path, &ParseOptions::default())?
+            .0)
+    }
+
+    /// Parses with the given options, returning the diagnostics recorded in lenient mode
+    /// alongside the root element.
+    pub fn root_element_with_options<T: HasBorrowed>(
+        &self,
+        path: &str,
+        options: &ParseOptions,
+    ) -> Result<(T::Borrowed<'_>, Vec<ParseDiagnostic>), SdkErrorReport> {
+        let Some(buffer) = self.get(path) else {
+            return Err(SdkError::CommonError(path.to_string()).into());
+        };
+
+        // Strings can only be borrowed from parts in UTF-8.
+        if XmlEncoding::detect(buffer)? != XmlEncoding::Utf8 {
+            return Err(SdkError::CommonError("borrowing requires UTF-8".to_string()).into());
+        }
+
+        let xml = std::str::from_utf8(buffer).map_err(|e| SdkError::CommonError(e.to_string()))?;
+
+        <T::Borrowed<'_> as BorrowedDeserializeable>::from_str_borrowed_with_options(xml, options)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::common::*;
+use std::{
+    io::{Cursor, Read, Write},
+    path::Path,
+};
+
+/// Packs the files of an unzipped package directory into a zip package.
+pub(crate) fn dir_to_zip<P: AsRef<Path>>(dir_path: P) -> Result<Vec<u8>, SdkErrorReport> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    let mut dir_path_list = vec![(dir_path.as_ref().to_path_buf(), "".to_string())];
+
+    while let Some((dir_path, entry_dir_path)) = dir_path_list.pop() {
+        let mut dir_entry_list = std::fs::read_dir(&dir_path)
+            .map_err(SdkError::from)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(SdkError::from)?;
+
+        dir_entry_list.sort_by_key(|dir_entry| dir_entry.file_name());
+
+        for dir_entry in dir_entry_list {
+            let entry_path = format!(
+                "{}{}",
+                entry_dir_path,
+                dir_entry.file_name().to_string_lossy()
+            );
+
+            if dir_entry.file_type().map_err(SdkError::from)?.is_dir() {
+                dir_path_list.push((dir_entry.path(), format!("{entry_path}/")));
+
+                continue;
+            }
+
+            let mut part_content = vec![];
+
+            std::fs::File::open(dir_entry.path())
+                .map_err(SdkError::from)?
+                .read_to_end(&mut part_content)
+                .map_err(SdkError::from)?;
+
+            zip.start_file(&entry_path, options)
+                .map_err(SdkError::from)?;
+
+            zip.write_all(&part_content).map_err(SdkError::from)?;
+        }
+    }
+
+    Ok(zip.finish().map_err(SdkError::from)?.into_inner())
+}
+
+/// Extracts every file entry of a zip package into a directory, overwriting existing files.
+pub(crate) fn zip_to_dir<R: Read + std::io::Seek, P: AsRef<Path>>(
+    reader: R,
+    dir_path: P,
+) -> Result<(), SdkErrorReport> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(SdkError::from)?;
+
+    for i in 0..archive.len() {
+        let mut zip_entry = archive.by_index(i).map_err(SdkError::from)?;
+
+        if zip_entry.is_dir() {
+            continue;
+        }
+
+        let Some(entry_path) = zip_entry.enclosed_name() else {
+            return Err(SdkError::CommonError(zip_entry.name().to_string()).into());
+        };
+
+        let file_path = dir_path.as_ref().join(entry_path);
+
+        if let Some(parent_path) = file_path.parent() {
+            std::fs::create_dir_all(parent_path).map_err(SdkError::from)?;
+        }
+
+        let mut file = std::fs::File::create(&file_path).map_err(SdkError::from)?;
+
+        std::io::copy(&mut zip_entry, &mut file).map_err(SdkError::from)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use crate::{
+    common::{opc_content_types::Types, opc_relationships::Relationships, *},
+    parts::{package_archive::PackageArchive, part_root_element::PartRootElement},
+};
+use std::collections::HashSet;
+
+/// The entries of a package being opened, e.g. the files of a zip archive.
+pub(crate) trait PackageSource {
+    /// Whether the package has an entry of that name.
+    fn contains(&self, path: &str) -> bool;
+
+    /// Opens an entry for reading.
+    fn open_entry(&mut self, path: &str) -> Result<Box<dyn std::io::Read + '_>, SdkErrorReport>;
+
+    /// The archive kept open for the root elements read from the package, if any.
+    fn package_archive(&self) -> Option<&PackageArchive> { return None; }
+}
+
+/// Reads the parts of a package from its source while walking the relationships.
+pub(crate) struct PackageReader<'a> {
+    source: Box<dyn PackageSource + 'a>,
+    lazy: bool,
+    pub(crate) content_types: Types,
+    pub(crate) ancestor_path_list: Vec<String>,
+}
+
+impl<'a> PackageReader<'a> {
+    /// Reads the content types of the package, leaving root elements pending until first access
+    /// if `lazy` is set and the source keeps its archive open.
+    pub(crate) fn new(
+        mut source: Box<dyn PackageSource + 'a>,
+        lazy: bool,
+    ) -> Result<Self, SdkErrorReport> {
+        let content_types = Types::from_reader(std::io::BufReader::new(
+            source.open_entry("[Content_Types].xml")?,
+        ))
+        .map_err(|e| e.attach("Reading part [[Content_Types].xml]"))?;
+
+        Ok(Self {
+            source,
+            lazy,
+            content_types,
+            ancestor_path_list: vec![],
+        })
+    }
+
+    /// Reads a zip archive, which parts keep referencing if `package_archive` is the archive.
+    pub(crate) fn from_zip<R: std::io::Read + std::io::Seek + 'a>(
+        archive: &'a mut zip::ZipArchive<R>,
+        package_archive: Option<&'a PackageArchive>,
+        lazy: bool,
+    ) -> Result<Self, SdkErrorReport> {
+        Self::new(
+            Box::new(ZipSource {
+                file_path_set: crate::parts::package_archive::archive_file_path_set(archive)?,
+                archive,
+                package_archive,
+            }),
+            lazy,
+        )
+    }
+
+    pub(crate) fn contains(&self, path: &str) -> bool { return self.source.contains(path); }
+
+    pub(crate) fn content_type(&self, path: &str) -> Option<String> {
+        return self.content_types.get_content_type(path).map(str::to_string);
+    }
+
+    /// Reads the whole content of an entry.
+    pub(crate) fn read_entry(&mut self, path: &str) -> Result<Vec<u8>, SdkErrorReport> {
+        use std::io::Read;
+
+        let mut part_content = vec![];
+
+        self.source
+            .open_entry(path)?
+            .read_to_end(&mut part_content)
+            .map_err(SdkError::from)
+            .map_err(|e| e.attach(format!("Reading part [{path}]")))?;
+
+        Ok(part_content)
+    }
+
+    /// Reads the relationships of the part at `path`, if it has any.
+    pub(crate) fn read_relationships(
+        &mut self,
+        path: &str,
+    ) -> Result<Option<(String, Relationships)>, SdkErrorReport> {
+        let rels_path = resolve_rels_path(path);
+
+        if !self.source.contains(&rels_path) {
+            return Ok(None);
+        }
+
+        let relationships =
+            Relationships::from_reader(std::io::BufReader::new(self.source.open_entry(&rels_path)?))
+                .map_err(|e| e.attach(format!("Reading part [{rels_path}]")))?;
+
+        Ok(Some((rels_path, relationships)))
+    }
+
+    /// Reads the root element of the part at `path`, parsing it now unless the package is loaded
+    /// lazily.
+    pub(crate) fn read_root_element<T: Deserializeable>(
+        &mut self,
+        path: &str,
+    ) -> Result<PartRootElement<T>, SdkErrorReport> {
+        let package_archive = self.source.package_archive().cloned();
+
+        if self.lazy
+            && let Some(package_archive) = &package_archive
+        {
+            return Ok(PartRootElement::new_lazy(package_archive, path));
+        }
+
+        let value = T::from_reader(std::io::BufReader::new(self.source.open_entry(path)?))
+            .map_err(|e| e.attach(format!("Reading part [{path}]")))?;
+
+        Ok(match &package_archive {
+            Some(package_archive) => PartRootElement::new_loaded(value, package_archive, path),
+            None => PartRootElement::new(value),
+        })
+    }
+}
+
+/// The entries of a zip archive.
+struct ZipSource<'a, R> {
+    archive: &'a mut zip::ZipArchive<R>,
+    file_path_set: HashSet<String>,
+    package_archive: Option<&'a PackageArchive>,
+}
+
+impl<R: std::io::Read + std::io::Seek> PackageSource for ZipSource<'_, R> {
+    fn contains(&self, path: &str) -> bool { return self.file_path_set.contains(path); }
+
+    fn open_entry(&mut self, path: &str) -> Result<Box<dyn std::io::Read + '_>, SdkErrorReport> {
+        Ok(Box::new(self.archive.by_name(path).map_err(SdkError::from)?))
+    }
+
+    fn package_archive(&self) -> Option<&PackageArchive> { return self.package_archive; }
+}
@@ -0,0 +1,230 @@
+use crate::{common::*, parts::package_archive::PackageArchive};
+use std::sync::OnceLock;
+
+/// The root element of a part, either parsed already or still pending in the archive of a lazily
+/// loaded package, in which case it is parsed on first access.
+///
+/// Root elements read from a package remember their archive entry. Until they are mutably
+/// borrowed or replaced, saving copies the original compressed entry instead of serializing them
+/// again.
+///
+/// The root element is accessed through [`Self::try_get`] and [`Self::try_get_mut`], which return
+/// the error of a pending root element failing to parse.
+#[derive(Clone)]
+pub struct PartRootElement<T> {
+    value: OnceLock<T>,
+    source: Option<(PackageArchive, String)>,
+    dirty: bool,
+}
+
+impl<T> PartRootElement<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: OnceLock::from(value),
+            source: None,
+            dirty: true,
+        }
+    }
+
+    pub(crate) fn new_loaded(value: T, package_archive: &PackageArchive, path: &str) -> Self {
+        Self {
+            value: OnceLock::from(value),
+            source: Some((package_archive.clone(), path.to_string())),
+            dirty: false,
+        }
+    }
+
+    pub(crate) fn new_lazy(package_archive: &PackageArchive, path: &str) -> Self {
+        Self {
+            value: OnceLock::new(),
+            source: Some((package_archive.clone(), path.to_string())),
+            dirty: false,
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool { return self.value.get().is_some(); }
+
+    /// Returns the archive and the entry the root element is read from, if any.
+    pub(crate) fn source(&self) -> Option<(&PackageArchive, &str)> {
+        return self
+            .source
+            .as_ref()
+            .map(|(package_archive, path)| (package_archive, path.as_str()));
+    }
+
+    /// Whether the root element has to be serialized on save, i.e. it was mutably borrowed,
+    /// replaced or created from scratch.
+    pub fn is_dirty(&self) -> bool { return self.dirty || self.source.is_none(); }
+
+    /// Forces the root element to be serialized on save.
+    pub fn mark_dirty(&mut self) { self.dirty = true; }
+
+    /// Reads the unparsed part content from the archive, if the root element is not loaded yet.
+    fn read_source(&self) -> Result<Option<Vec<u8>>, SdkErrorReport> {
+        use std::io::Read;
+
+        let Some((package_archive, path)) = &self.source else {
+            return Ok(None);
+        };
+
+        let mut archive = package_archive.lock()?;
+
+        let mut zip_entry = archive.by_name(path).map_err(SdkError::from)?;
+
+        let mut part_content = Vec::with_capacity(zip_entry.size() as usize);
+
+        zip_entry
+            .read_to_end(&mut part_content)
+            .map_err(SdkError::from)?;
+
+        Ok(Some(part_content))
+    }
+
+    /// Returns the encoding of the part content in the archive, or UTF-8 for root elements
+    /// created from scratch.
+    pub fn source_encoding(&self) -> Result<XmlEncoding, SdkErrorReport> {
+        use std::io::Read;
+
+        let Some((package_archive, path)) = &self.source else {
+            return Ok(XmlEncoding::Utf8);
+        };
+
+        let mut archive = package_archive.lock()?;
+
+        let zip_entry = archive.by_name(path).map_err(SdkError::from)?;
+
+        // Long enough for any XML declaration.
+        let mut part_start = Vec::with_capacity(1024);
+
+        zip_entry
+            .take(1024)
+            .read_to_end(&mut part_start)
+            .map_err(SdkError::from)?;
+
+        XmlEncoding::detect(&part_start)
+    }
+}
+
+impl<T: Deserializeable> PartRootElement<T> {
+    pub fn try_get(&self) -> Result<&T, SdkErrorReport> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let part_content = self
+            .read_source()?
+            .ok_or_else(|| SdkError::CommonError("root_element".to_string()))?;
+
+        let value = T::from_reader(&part_content[..]).map_err(|e| {
+            e.attach(format!(
+                "Reading part [{}]",
+                self.source.as_ref().map_or("", |(_, path)| path)
+            ))
+        })?;
+
+        let _ = self.value.set(value);
+
+        Ok(self.value.get().unwrap())
+    }
+
+    pub fn try_get_mut(&mut self) -> Result<&mut T, SdkErrorReport> {
+        self.try_get()?;
+
+        self.dirty = true;
+
+        Ok(self.value.get_mut().unwrap())
+    }
+
+    pub fn into_inner(mut self) -> Result<T, SdkErrorReport> {
+        self.try_get()?;
+
+        Ok(self.value.take().unwrap())
+    }
+}
+
+impl<T: Serializeable + Deserializeable> PartRootElement<T> {
+    /// Writes the part entry, copying the original compressed entry if the root element was never
+    /// loaded or is not dirty, unless the save options change how parts are serialized.
+    pub(crate) fn save_zip_entry<W: std::io::Write + std::io::Seek>(
+        &self,
+        zip: &mut zip::ZipWriter<W>,
+        inner_path: &str,
+        options: zip::write::SimpleFileOptions,
+        save_options: &SaveOptions,
+    ) -> Result<(), SdkErrorReport> {
+        use std::io::Write;
+
+        if let Some((package_archive, path)) = &self.source
+            && (!self.dirty || !self.is_loaded())
+            && !save_options.reserializes_parts()
+        {
+            let mut archive = package_archive.lock()?;
+
+            // The original entry carries its own timestamp and permissions.
+            if save_options.deterministic {
+                let mut zip_entry = archive.by_name(path).map_err(SdkError::from)?;
+
+                zip.start_file(inner_path, options)
+                    .map_err(SdkError::from)?;
+
+                std::io::copy(&mut zip_entry, zip).map_err(SdkError::from)?;
+
+                return Ok(());
+            }
+
+            let index = archive
+                .index_for_name(path)
+                .ok_or_else(|| SdkError::CommonError(path.to_string()))?;
+
+            let zip_entry = archive.by_index_raw(index).map_err(SdkError::from)?;
+
+            zip.raw_copy_file_rename(zip_entry, inner_path)
+                .map_err(SdkError::from)?;
+
+            return Ok(());
+        }
+
+        let value = self.try_get()?;
+
+        let encoding = if save_options.keep_encoding {
+            self.source_encoding()?
+        } else {
+            XmlEncoding::Utf8
+        };
+
+        zip.start_file(inner_path, options)
+            .map_err(SdkError::from)?;
+
+        if encoding == XmlEncoding::Utf8 {
+            value
+                .write_xml_with_options(&mut *zip, true, true, &save_options.serialize_options)
+                .map_err(SdkError::from)?;
+        } else {
+            zip.write_all(&encode_xml_string(
+                &value.to_xml_string_with_options(true, true, &save_options.serialize_options),
+                encoding,
+            ))
+            .map_err(SdkError::from)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> From<T> for PartRootElement<T> {
+    fn from(value: T) -> Self { Self::new(value) }
+}
+
+impl<T: Default> Default for PartRootElement<T> {
+    fn default() -> Self { Self::new(T::default()) }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for PartRootElement<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.value.get(), &self.source) {
+            (Some(value), _) => value.fmt(f),
+            (None, Some((_, path))) => f.debug_tuple("Unloaded").field(path).finish(),
+            (None, None) => f.write_str("Unloaded"),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    common::{
+        opc_relationships::{Relationship, Relationships, TargetMode},
+        *,
+    },
+    parts::package_reader::PackageReader,
+};
+use std::collections::HashSet;
+
+/// A part reached through a relationship type without a generated part, e.g. custom parts,
+/// vendor extensions or parts introduced by newer Office versions.
+///
+/// The part content is kept verbatim together with its own relationships, so that opening and
+/// saving a package does not lose it.
+#[derive(Clone, Debug, Default)]
+pub struct UnknownPart {
+    pub r_id: String,
+    pub relationship_type: String,
+    pub content_type: Option<String>,
+    pub relationships: Option<Relationships>,
+    pub rels_path: String,
+    pub inner_path: String,
+    pub part_content: Vec<u8>,
+    pub unknown_parts: Vec<UnknownPart>,
+}
+
+impl UnknownPart {
+    pub(crate) fn new_from_package_reader(
+        path: &str,
+        relationship: &Relationship,
+        package_reader: &mut PackageReader<'_>,
+    ) -> Result<Self, SdkErrorReport> {
+        let part_content = package_reader.read_entry(path)?;
+
+        let (part_dir_path, _) = split_part_path(path);
+
+        let (rels_path, relationships) = match package_reader.read_relationships(path)? {
+            Some((rels_path, relationships)) => (rels_path, Some(relationships)),
+            None => ("".to_string(), None),
+        };
+
+        let mut unknown_parts = vec![];
+
+        if let Some(relationships) = &relationships {
+            package_reader.ancestor_path_list.push(path.to_string());
+
+            for child_relationship in &relationships.relationship {
+                if let Some(TargetMode::External) = child_relationship.target_mode {
+                    continue;
+                }
+
+                let target_path =
+                    resolve_relationship_target(part_dir_path, &child_relationship.target);
+
+                if package_reader.ancestor_path_list.contains(&target_path) {
+                    continue;
+                }
+
+                if !package_reader.contains(&target_path) {
+                    tracing::warn!(
+                        "Skipping relationship ({}) with missing target: ({})",
+                        child_relationship.id,
+                        target_path
+                    );
+
+                    continue;
+                }
+
+                unknown_parts.push(Self::new_from_package_reader(
+                    &target_path,
+                    child_relationship,
+                    package_reader,
+                )?);
+            }
+
+            package_reader.ancestor_path_list.pop();
+        }
+
+        Ok(Self {
+            r_id: relationship.id.to_string(),
+            relationship_type: relationship.r#type.to_string(),
+            content_type: package_reader.content_type(path),
+            relationships,
+            rels_path,
+            inner_path: path.to_string(),
+            part_content,
+            unknown_parts,
+        })
+    }
+
+    pub(crate) fn save_zip<W: std::io::Write + std::io::Seek>(
+        &self,
+        zip: &mut zip::ZipWriter<W>,
+        entry_set: &mut HashSet<String>,
+        save_options: &SaveOptions,
+    ) -> Result<(), SdkErrorReport> {
+        use std::io::Write;
+
+        let options = save_options.zip_file_options();
+
+        if !entry_set.contains(&self.inner_path) {
+            zip.start_file(&self.inner_path, options)
+                .map_err(SdkError::from)?;
+
+            zip.write_all(&self.part_content).map_err(SdkError::from)?;
+
+            entry_set.insert(self.inner_path.to_string());
+        }
+
+        if let Some(relationships) = &self.relationships
+            && !entry_set.contains(&self.rels_path)
+        {
+            zip.start_file(&self.rels_path, options)
+                .map_err(SdkError::from)?;
+
+            relationships
+                .write_xml_with_options(&mut *zip, true, true, &save_options.serialize_options)
+                .map_err(SdkError::from)?;
+
+            entry_set.insert(self.rels_path.to_string());
+        }
+
+        for unknown_part in &self.unknown_parts {
+            unknown_part.save_zip(zip, entry_set, save_options)?;
+        }
+
+        Ok(())
+    }
+}
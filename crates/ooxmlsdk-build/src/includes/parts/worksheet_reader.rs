@@ -0,0 +1,195 @@
+use crate::{
+    common::*,
+    parts::{package_archive::ReadSeek, worksheet_part::WorksheetPart},
+    schemas::schemas_openxmlformats_org_spreadsheetml_2006_main::{Row, Worksheet},
+};
+use quick_xml::{
+    Decoder,
+    events::{BytesStart, Event},
+    name::QName,
+};
+use rootcause::prelude::ResultExt;
+use std::io::BufRead;
+
+const NAMESPACE_URI: &[u8] = b"http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+
+/// The row reader handed out by [`WorksheetPart::read_rows`], reading straight from the zip entry
+/// of the part.
+pub type ZipEntryRowReader<'a> =
+    WorksheetRowReader<std::io::BufReader<zip::read::ZipFile<'a, Box<dyn ReadSeek>>>>;
+
+/// Reads the rows of a worksheet one at a time, so that memory use does not grow with the number
+/// of rows.
+///
+/// Each row is parsed by the generated [`Row`] deserializer as it is pulled from the iterator;
+/// everything outside of `x:sheetData` is skipped, see [`read_worksheet_without_rows`] for it.
+pub struct WorksheetRowReader<R: BufRead> {
+    xml_reader: IoReader<R>,
+    finished: bool,
+}
+
+impl<R: BufRead> WorksheetRowReader<R> {
+    /// Reads up to the start of `x:sheetData` of a worksheet part in UTF-8.
+    pub fn new(mut reader: R) -> Result<Self, SdkErrorReport> {
+        if XmlEncoding::detect(reader.fill_buf().map_err(SdkError::from)?)? != XmlEncoding::Utf8 {
+            return Err(SdkError::CommonError("streaming requires UTF-8".to_string()).into());
+        }
+
+        let mut xml_reader = quick_xml::NsReader::from_reader(reader);
+        xml_reader.config_mut().check_end_names = false;
+        xml_reader.config_mut().trim_text(false);
+
+        let mut xml_reader = IoReader::new(xml_reader);
+
+        loop {
+            let finished = match xml_reader.next()? {
+                Event::Start(e) if is_sheet_data(&xml_reader, &e) => false,
+                Event::Empty(e) if is_sheet_data(&xml_reader, &e) => true,
+                Event::Eof => {
+                    return Err(SdkError::CommonError("x:sheetData".to_string()))
+                        .attach_with(|| xml_reader.location());
+                }
+                _ => continue,
+            };
+
+            return Ok(Self {
+                xml_reader,
+                finished,
+            });
+        }
+    }
+
+    fn next_row(&mut self) -> Result<Option<Row>, SdkErrorReport> {
+        loop {
+            match self.xml_reader.next()? {
+                Event::Start(e) if is_row(&self.xml_reader, &e) => {
+                    return Ok(Some(Row::deserialize_inner(
+                        &mut self.xml_reader,
+                        Some((e, false)),
+                    )?));
+                }
+                Event::Empty(e) if is_row(&self.xml_reader, &e) => {
+                    return Ok(Some(Row::deserialize_inner(
+                        &mut self.xml_reader,
+                        Some((e, true)),
+                    )?));
+                }
+                Event::Start(_) => self.xml_reader.skip_element()?,
+                Event::End(_) => return Ok(None),
+                Event::Eof => Err(SdkError::UnknownError)?,
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for WorksheetRowReader<R> {
+    type Item = Result<Row, SdkErrorReport>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.next_row().attach_with(|| self.xml_reader.location());
+
+        // Stop after the end of `x:sheetData` and after any error, as the reader is left in the
+        // middle of an element.
+        if !matches!(result, Ok(Some(_))) {
+            self.finished = true;
+        }
+
+        return result.transpose();
+    }
+}
+
+fn is_sheet_data<'de>(xml_reader: &impl XmlReader<'de>, e: &BytesStart) -> bool {
+    return xml_reader.resolve_element(e.name()) == (Some(NAMESPACE_URI), b"sheetData");
+}
+
+fn is_row<'de>(xml_reader: &impl XmlReader<'de>, e: &BytesStart) -> bool {
+    return xml_reader.resolve_element(e.name()) == (Some(NAMESPACE_URI), b"row");
+}
+
+/// Deserializes a worksheet part with an empty `x:sheetData`, skipping the rows instead of
+/// materializing them.
+pub fn read_worksheet_without_rows(reader: impl BufRead) -> Result<Worksheet, SdkErrorReport> {
+    let mut xml_reader = quick_xml::NsReader::from_reader(reader);
+    xml_reader.config_mut().check_end_names = false;
+    xml_reader.config_mut().trim_text(false);
+
+    let mut xml_reader = SheetDataSkippingReader(IoReader::new(xml_reader));
+
+    Worksheet::deserialize_inner(&mut xml_reader, None).attach_with(|| xml_reader.location())
+}
+
+/// Passes `x:sheetData` on as an empty element, skipping its content.
+struct SheetDataSkippingReader<X>(X);
+
+impl<'de, X: XmlReader<'de>> XmlReader<'de> for SheetDataSkippingReader<X> {
+    fn next(&mut self) -> Result<Event<'de>, SdkErrorReport> {
+        match self.0.next()? {
+            Event::Start(e) if is_sheet_data(&self.0, &e) => {
+                self.0.skip_element()?;
+
+                Ok(Event::Empty(e))
+            }
+            event => Ok(event),
+        }
+    }
+
+    fn decoder(&self) -> Decoder { self.0.decoder() }
+
+    fn resolve_element<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]) {
+        self.0.resolve_element(name)
+    }
+
+    fn resolve_attribute<'n>(&self, name: QName<'n>) -> (Option<&[u8]>, &'n [u8]) {
+        self.0.resolve_attribute(name)
+    }
+
+    fn parse_mode(&self) -> ParseMode { self.0.parse_mode() }
+
+    fn element_path(&self) -> &str { self.0.element_path() }
+
+    fn location(&self) -> SourceLocation { self.0.location() }
+
+    fn diagnostics_mut(&mut self) -> &mut Vec<ParseDiagnostic> { self.0.diagnostics_mut() }
+}
+
+impl WorksheetPart {
+    /// Streams the rows of the worksheet from the package archive, handing the worksheet with an
+    /// empty `x:sheetData` and a reader of its rows to `f`.
+    ///
+    /// The part is read from the archive even if the root element is loaded already; open the
+    /// package with `new_lazy` to avoid materializing it. Modified root elements cannot be
+    /// streamed.
+    pub fn read_rows<T>(
+        &self,
+        f: impl FnOnce(&Worksheet, &mut ZipEntryRowReader<'_>) -> Result<T, SdkErrorReport>,
+    ) -> Result<T, SdkErrorReport> {
+        if self.root_element.is_loaded() && self.root_element.is_dirty() {
+            return Err(SdkError::CommonError("root_element".to_string()))
+                .attach("Streaming a modified worksheet");
+        }
+
+        let Some((package_archive, path)) = self.root_element.source() else {
+            return Err(SdkError::CommonError("root_element".to_string()))
+                .attach("Streaming a worksheet not read from a package");
+        };
+
+        let mut archive = package_archive.lock()?;
+
+        let worksheet = read_worksheet_without_rows(std::io::BufReader::new(
+            archive.by_name(path).map_err(SdkError::from)?,
+        ))
+        .map_err(|e| e.attach(format!("Reading part [{path}]")))?;
+
+        let mut row_reader = WorksheetRowReader::new(std::io::BufReader::new(
+            archive.by_name(path).map_err(SdkError::from)?,
+        ))
+        .map_err(|e| e.attach(format!("Reading part [{path}]")))?;
+
+        f(&worksheet, &mut row_reader)
+    }
+}
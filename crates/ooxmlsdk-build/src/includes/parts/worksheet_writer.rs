@@ -0,0 +1,153 @@
+use crate::{
+    common::*,
+    parts::{spreadsheet_document::SpreadsheetDocument, worksheet_part::WorksheetPart},
+    schemas::schemas_openxmlformats_org_spreadsheetml_2006_main::{Row, SheetData},
+};
+use rootcause::prelude::ResultExt;
+use std::{
+    collections::HashSet,
+    io::{Seek, Write},
+};
+
+/// Saves a spreadsheet package while streaming the rows of some of its worksheets, so that memory
+/// use does not grow with the number of rows.
+///
+/// Worksheets are streamed with [`Self::write_worksheet`] first; [`Self::finish`] then writes every
+/// other entry of the package as [`SpreadsheetDocument::save`] does.
+pub struct SpreadsheetDocumentWriter<'a, W: Write + Seek> {
+    package: &'a SpreadsheetDocument,
+    zip: zip::ZipWriter<W>,
+    entry_set: HashSet<String>,
+    options: zip::write::SimpleFileOptions,
+    save_options: SaveOptions,
+}
+
+impl SpreadsheetDocument {
+    /// Starts saving the package into `writer`, see [`SpreadsheetDocumentWriter`].
+    pub fn save_streaming<W: Write + Seek>(
+        &self,
+        writer: W,
+    ) -> Result<SpreadsheetDocumentWriter<'_, W>, SdkErrorReport> {
+        self.save_streaming_with_options(writer, &SaveOptions::default())
+    }
+
+    /// Starts saving the package into `writer` with the given options, e.g. deterministic.
+    pub fn save_streaming_with_options<W: Write + Seek>(
+        &self,
+        writer: W,
+        save_options: &SaveOptions,
+    ) -> Result<SpreadsheetDocumentWriter<'_, W>, SdkErrorReport> {
+        let mut zip = zip::ZipWriter::new(writer);
+
+        let options = save_options.zip_file_options();
+
+        zip.start_file("[Content_Types].xml", options)
+            .map_err(SdkError::from)?;
+
+        self.content_types
+            .write_xml_with_options(&mut zip, true, true, &save_options.serialize_options)
+            .map_err(SdkError::from)?;
+
+        Ok(SpreadsheetDocumentWriter {
+            package: self,
+            zip,
+            entry_set: HashSet::new(),
+            options,
+            save_options: save_options.clone(),
+        })
+    }
+}
+
+impl<'a, W: Write + Seek> SpreadsheetDocumentWriter<'a, W> {
+    /// Writes the entry of `worksheet_part` up to the end of its `x:sheetData`, including the rows
+    /// of its root element, and returns a writer of the following rows.
+    ///
+    /// The returned writer has to be finished before the package is.
+    pub fn write_worksheet(
+        &mut self,
+        worksheet_part: &WorksheetPart,
+    ) -> Result<WorksheetWriter<'_, W>, SdkErrorReport> {
+        let inner_path = worksheet_part.inner_path.as_str();
+
+        if self.entry_set.contains(inner_path) {
+            return Err(SdkError::CommonError(inner_path.to_string()))
+                .attach("Streaming a worksheet twice");
+        }
+
+        let xml = worksheet_part
+            .root_element
+            .try_get()?
+            .to_xml_string(true, true);
+
+        let sheet_data_end = format!("</{}>", SheetData::PREFIXED_NAME);
+
+        let (header, trailer) = xml
+            .rfind(&sheet_data_end)
+            .map(|index| xml.split_at(index))
+            .ok_or(SdkError::CommonError(sheet_data_end))?;
+
+        for (index, _) in inner_path.match_indices('/') {
+            let directory_path = resolve_zip_file_path(&inner_path[..index]);
+
+            if !directory_path.is_empty() && !self.entry_set.contains(&directory_path) {
+                self.zip
+                    .add_directory(&directory_path, self.options)
+                    .map_err(SdkError::from)?;
+
+                self.entry_set.insert(directory_path);
+            }
+        }
+
+        self.zip
+            .start_file(inner_path, self.options)
+            .map_err(SdkError::from)?;
+
+        self.zip
+            .write_all(header.as_bytes())
+            .map_err(SdkError::from)?;
+
+        self.entry_set.insert(inner_path.to_string());
+
+        Ok(WorksheetWriter {
+            zip: &mut self.zip,
+            trailer: trailer.to_string(),
+        })
+    }
+
+    /// Writes the remaining entries of the package and finishes the archive.
+    pub fn finish(mut self) -> Result<W, SdkErrorReport> {
+        self.package
+            .save_zip("", &mut self.zip, &mut self.entry_set, &self.save_options)?;
+
+        let writer = self.zip.finish().map_err(SdkError::from)?;
+
+        Ok(writer)
+    }
+}
+
+/// Writes rows into the `x:sheetData` of a worksheet entry, one at a time.
+///
+/// The entry is incomplete until [`Self::finish`] is called.
+pub struct WorksheetWriter<'a, W: Write + Seek> {
+    zip: &'a mut zip::ZipWriter<W>,
+    trailer: String,
+}
+
+impl<W: Write + Seek> WorksheetWriter<'_, W> {
+    /// Serializes `row` straight into the entry.
+    pub fn write_row(&mut self, row: &Row) -> Result<(), SdkErrorReport> {
+        row.write_xml(&mut *self.zip, false, true)
+            .map_err(SdkError::from)?;
+
+        Ok(())
+    }
+
+    /// Writes the end of `x:sheetData` and everything following it.
+    pub fn finish(self) -> Result<(), SdkErrorReport> {
+        self.zip
+            .write_all(self.trailer.as_bytes())
+            .map_err(SdkError::from)?;
+
+        Ok(())
+    }
+}
@@ -8,8 +8,12 @@ use syn::{Ident, ItemMod, parse_quote, parse_str};
 use crate::{
     error::*,
     generator::{
-        context::GenContext, deserializer::gen_deserializers,
-        open_xml_schema::gen_open_xml_schemas, serializer::gen_serializer,
+        context::GenContext,
+        deserializer::{
+            gen_deserializers, gen_is_namespace_understood_fn, gen_namespace_uri_by_prefix_fn,
+        },
+        open_xml_schema::gen_open_xml_schemas,
+        serializer::gen_serializer,
     },
     utils::HashMapOpsError,
 };
@@ -47,6 +51,10 @@ pub fn generate_with(
             .insert(&namespace.uri, namespace);
     }
 
+    for part in gen_context.parts.iter() {
+        gen_context.part_name_part_map.insert(&part.name, part);
+    }
+
     for typed_namespace in gen_context.typed_namespaces.iter() {
         gen_context
             .namespace_typed_namespace_map
@@ -115,6 +123,8 @@ pub fn generate_with(
         write_parts,
         #[cfg(feature = "validators")]
         write_validators,
+        #[cfg(feature = "borrowed")]
+        write_borrowed,
     ]
     .par_iter()
     .map(|task| task(&gen_context, out_dir))
@@ -124,7 +134,7 @@ pub fn generate_with(
 }
 
 pub(crate) fn write_common(
-    _gen_context: &GenContext,
+    gen_context: &GenContext,
     out_base_dir: &Path,
 ) -> Result<(), BuildErrorReport> {
     let out_dir = out_base_dir.join("common");
@@ -154,7 +164,21 @@ pub(crate) fn write_common(
     )
     .map_err(BuildError::from)?;
 
+    fs::write(
+        out_dir.join("namespace.rs"),
+        [
+            gen_namespace_uri_by_prefix_fn(gen_context)?,
+            gen_is_namespace_understood_fn(gen_context)?,
+        ]
+        .iter()
+        .map(|item_fn| item_fn.to_token_stream().to_string())
+        .collect::<Vec<_>>()
+        .join("\n"),
+    )
+    .map_err(BuildError::from)?;
+
     let mut mod_rs_content = quote! {
+        pub mod namespace;
         pub mod simple_type;
         pub mod opc_content_types;
         pub mod opc_core_properties;
@@ -245,12 +269,85 @@ pub(crate) fn write_parts(
     gen_context: &GenContext,
     out_base_dir: &Path,
 ) -> Result<(), BuildErrorReport> {
-    use crate::generator::open_xml_part::gen_open_xml_parts;
+    use crate::generator::open_xml_part::{
+        gen_data_part_reference_relationship, gen_open_xml_parts,
+    };
+    use heck::ToSnakeCase;
+    use std::collections::BTreeSet;
 
     let out_dir = &out_base_dir.join("parts");
     fs::create_dir_all(out_dir).map_err(BuildError::from)?;
 
-    let mod_rs_lines = gen_context
+    fs::write(
+        out_dir.join("unknown_part.rs"),
+        include_bytes!("includes/parts/unknown_part.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("media_data_part.rs"),
+        include_bytes!("includes/parts/media_data_part.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("document_type.rs"),
+        include_bytes!("includes/parts/document_type.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("flat_opc.rs"),
+        include_bytes!("includes/parts/flat_opc.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("package_archive.rs"),
+        include_bytes!("includes/parts/package_archive.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("package_dir.rs"),
+        include_bytes!("includes/parts/package_dir.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("package_reader.rs"),
+        include_bytes!("includes/parts/package_reader.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    fs::write(
+        out_dir.join("part_root_element.rs"),
+        include_bytes!("includes/parts/part_root_element.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    #[cfg(feature = "borrowed")]
+    fs::write(
+        out_dir.join("package_buffers.rs"),
+        include_bytes!("includes/parts/package_buffers.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    #[cfg(feature = "xlsx")]
+    fs::write(
+        out_dir.join("worksheet_reader.rs"),
+        include_bytes!("includes/parts/worksheet_reader.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    #[cfg(feature = "xlsx")]
+    fs::write(
+        out_dir.join("worksheet_writer.rs"),
+        include_bytes!("includes/parts/worksheet_writer.rs"),
+    )
+    .map_err(BuildError::from)?;
+
+    let mut mod_rs_lines = gen_context
         .parts
         .par_iter()
         .map(|part| {
@@ -262,6 +359,60 @@ pub(crate) fn write_parts(
         })
         .collect::<Result<Vec<_>, _>>()?;
 
+    let data_part_reference_name_set: BTreeSet<&str> = gen_context
+        .parts
+        .iter()
+        .flat_map(|part| part.children.iter())
+        .filter(|child| child.is_data_part_reference)
+        .map(|child| child.name.as_str())
+        .collect();
+
+    for data_part_reference_name in data_part_reference_name_set {
+        mod_rs_lines.push(generate_pub_item_mod(
+            out_dir,
+            &data_part_reference_name.to_snake_case(),
+            &gen_data_part_reference_relationship(data_part_reference_name)?,
+        )?);
+    }
+
+    mod_rs_lines.push(
+        quote! {
+            pub mod media_data_part;
+            pub mod document_type;
+            pub mod flat_opc;
+            pub mod package_archive;
+            pub mod package_dir;
+            pub mod package_reader;
+            pub mod part_root_element;
+            pub mod unknown_part;
+        }
+        .to_string(),
+    );
+
+    #[cfg(feature = "borrowed")]
+    mod_rs_lines.push(
+        quote! {
+            pub mod package_buffers;
+        }
+        .to_string(),
+    );
+
+    #[cfg(feature = "xlsx")]
+    mod_rs_lines.push(
+        quote! {
+            pub mod worksheet_reader;
+        }
+        .to_string(),
+    );
+
+    #[cfg(feature = "xlsx")]
+    mod_rs_lines.push(
+        quote! {
+            pub mod worksheet_writer;
+        }
+        .to_string(),
+    );
+
     fs::write(out_dir.join("mod.rs"), mod_rs_lines.join("\n")).map_err(BuildError::from)?;
 
     Ok(())
@@ -294,6 +445,37 @@ pub(crate) fn write_validators(
     Ok(())
 }
 
+#[cfg(feature = "borrowed")]
+pub(crate) fn write_borrowed(
+    gen_context: &GenContext,
+    out_base_dir: &Path,
+) -> Result<(), BuildErrorReport> {
+    use crate::generator::{
+        deserializer::gen_borrowed_deserializers, open_xml_schema::gen_borrowed_open_xml_schemas,
+    };
+
+    let out_dir = &out_base_dir.join("borrowed");
+    fs::create_dir_all(out_dir).map_err(BuildError::from)?;
+
+    let mod_rs_lines = gen_context
+        .schemas
+        .par_iter()
+        .map(|schema| {
+            return generate_pub_item_mod(
+                out_dir,
+                &schema.module_name,
+                &(gen_borrowed_open_xml_schemas(schema, gen_context)?
+                    + "\n"
+                    + &gen_borrowed_deserializers(schema, gen_context)?),
+            );
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    fs::write(out_dir.join("mod.rs"), mod_rs_lines.join("\n")).map_err(BuildError::from)?;
+
+    Ok(())
+}
+
 pub(crate) fn generate_pub_item_mod(
     directory: &Path,
     module_name: &str,
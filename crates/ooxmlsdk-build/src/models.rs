@@ -90,6 +90,15 @@ pub struct OpenXmlSchemaType {
 }
 
 impl OpenXmlSchemaType {
+    /// Whether elements of this type hold child elements, i.e. may hold raw children as well.
+    #[inline(always)]
+    pub fn is_composite(&self) -> bool {
+        self.base_class == "OpenXmlCompositeElement"
+            || self.base_class == "CustomXmlElement"
+            || self.base_class == "OpenXmlPartRootElement"
+            || self.base_class == "SdtElement"
+    }
+
     #[inline(always)]
     pub fn is_one_sequence_flatten(&self) -> bool {
         (self.composite_type == "OneSequence" || self.particle.kind == "Sequence")
@@ -23,9 +23,10 @@ fn main() -> Result<(), Report> {
         let docx_xml = docx
             .main_document_part
             .root_element
+            .try_get()?
             .to_xml_string(true, true);
         println!("{docx_xml}");
-        assert!(docx.main_document_part.root_element.validate()?);
+        assert!(docx.main_document_part.root_element.try_get()?.validate()?);
 
         let reader = BufReader::new(File::open(SAMPLE_DOCX_FILE_PATH)?);
         let reader_docx = WordprocessingDocument::new(reader)?;
@@ -33,6 +34,7 @@ fn main() -> Result<(), Report> {
             reader_docx
                 .main_document_part
                 .root_element
+                .try_get()?
                 .to_xml_string(true, true),
             docx_xml
         );
@@ -46,9 +48,10 @@ fn main() -> Result<(), Report> {
         let pptx_xml = pptx
             .presentation_part
             .root_element
+            .try_get()?
             .to_xml_string(true, true);
         println!("{pptx_xml}");
-        assert!(pptx.presentation_part.root_element.validate()?);
+        assert!(pptx.presentation_part.root_element.try_get()?.validate()?);
 
         let reader = BufReader::new(File::open(SAMPLE_PPTX_FILE_PATH)?);
         let reader_pptx = PresentationDocument::new(reader)?;
@@ -56,6 +59,7 @@ fn main() -> Result<(), Report> {
             reader_pptx
                 .presentation_part
                 .root_element
+                .try_get()?
                 .to_xml_string(true, true),
             pptx_xml
         );
@@ -66,9 +70,13 @@ fn main() -> Result<(), Report> {
 
     {
         let xlsx = SpreadsheetDocument::new_from_file(SAMPLE_XLSX_FILE_PATH).unwrap();
-        let xlsx_xml = xlsx.workbook_part.root_element.to_xml_string(true, true);
+        let xlsx_xml = xlsx
+            .workbook_part
+            .root_element
+            .try_get()?
+            .to_xml_string(true, true);
         println!("{xlsx_xml}");
-        assert!(xlsx.workbook_part.root_element.validate()?);
+        assert!(xlsx.workbook_part.root_element.try_get()?.validate()?);
 
         let reader = BufReader::new(File::open(SAMPLE_XLSX_FILE_PATH)?);
         let reader_xlsx = SpreadsheetDocument::new(reader)?;
@@ -76,6 +84,7 @@ fn main() -> Result<(), Report> {
             reader_xlsx
                 .workbook_part
                 .root_element
+                .try_get()?
                 .to_xml_string(true, true),
             xlsx_xml
         );
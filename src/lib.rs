@@ -23,3 +23,8 @@ pub mod parts {
 pub mod validators {
     include!(concat!(env!("OUT_DIR"), "/validators/mod.rs"));
 }
+
+#[cfg(feature = "borrowed")]
+pub mod borrowed {
+    include!(concat!(env!("OUT_DIR"), "/borrowed/mod.rs"));
+}
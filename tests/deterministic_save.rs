@@ -0,0 +1,71 @@
+use ooxmlsdk::{common::SaveOptions, parts::spreadsheet_document::SpreadsheetDocument};
+use rootcause::prelude::*;
+use std::io::{Cursor, Read, Write};
+
+/// Copies a zip package, stamping every entry with the given modification time.
+fn touch_package(package: &[u8], last_modified_time: zip::DateTime) -> Result<Vec<u8>, Report> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(package))?;
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(last_modified_time);
+
+    for i in 0..archive.len() {
+        let mut zip_entry = archive.by_index(i)?;
+
+        if zip_entry.is_dir() {
+            continue;
+        }
+
+        let mut content = vec![];
+        zip_entry.read_to_end(&mut content)?;
+
+        zip.start_file(zip_entry.name(), options)?;
+        zip.write_all(&content)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+#[test]
+fn test_deterministic_save_ignores_source_timestamps() -> Result<(), Report> {
+    let mut package = Cursor::new(Vec::new());
+    SpreadsheetDocument::create()?.save(&mut package)?;
+
+    let save_options = SaveOptions {
+        deterministic: true,
+        ..Default::default()
+    };
+
+    let mut saved_packages = vec![];
+
+    for last_modified_time in [
+        zip::DateTime::from_date_and_time(2001, 2, 3, 4, 5, 6)?,
+        zip::DateTime::from_date_and_time(2024, 10, 11, 12, 13, 14)?,
+    ] {
+        // Opened lazily, so that the unmodified parts are copied from the source entries.
+        let xlsx = SpreadsheetDocument::new_lazy(Cursor::new(touch_package(
+            package.get_ref(),
+            last_modified_time,
+        )?))?;
+
+        let mut saved_package = Cursor::new(Vec::new());
+        xlsx.save_with_options(&mut saved_package, &save_options)?;
+
+        saved_packages.push(saved_package.into_inner());
+    }
+
+    assert_eq!(saved_packages[0], saved_packages[1]);
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(&saved_packages[0]))?;
+
+    for i in 0..archive.len() {
+        assert_eq!(
+            archive.by_index(i)?.last_modified(),
+            Some(zip::DateTime::default())
+        );
+    }
+
+    Ok(())
+}